@@ -0,0 +1,59 @@
+use std::time::Instant;
+
+use tic_tac_toe::tictactoe::bitboard::{bits, Bitboard, BitboardLayout};
+use tic_tac_toe::tictactoe::game::TicTacToe;
+use tic_tac_toe::tictactoe::player::Player;
+use tic_tac_toe::tictactoe::rules::Rules;
+
+// Positions reached by playing every legal move down to `depth`
+fn count_game(game: &TicTacToe, depth: usize) -> usize {
+    if depth == 0 || game.is_game_over() {
+        return 1;
+    }
+
+    let moves: Vec<_> = if game.gravity() {
+        (0..game.state().width()).filter_map(|x| game.drop_target(x, 0)).collect()
+    } else {
+        game.state().cells().filter(|(_, cell)| **cell == Player::NONE).map(|(coords, _)| coords).collect()
+    };
+
+    1 + moves.into_iter().map(|coords| {
+        let mut next = game.clone();
+        next.place(coords).expect("legal move");
+        count_game(&next, depth - 1)
+    }).sum::<usize>()
+}
+
+fn count_bitboard(layout: &BitboardLayout, bitboard: &Bitboard, depth: usize) -> usize {
+    let moves = layout.moves(bitboard);
+    if depth == 0 || moves == 0 {
+        return 1;
+    }
+
+    1 + bits(moves)
+        .map(|index| count_bitboard(layout, &layout.play(bitboard, index, &Player::ONE), depth - 1))
+        .sum::<usize>()
+}
+
+fn bench(name: &str, game: TicTacToe, depth: usize) {
+    let start = Instant::now();
+    let positions = count_game(&game, depth);
+    let game_time = start.elapsed();
+
+    let layout = BitboardLayout::new(&game).expect("board fits in 64 cells");
+    let start = Instant::now();
+    let bitboard_positions = count_bitboard(&layout, &layout.encode(&game), depth);
+    let bitboard_time = start.elapsed();
+
+    assert_eq!(positions, bitboard_positions);
+    let per_second = |elapsed: std::time::Duration| positions as f64 / elapsed.as_secs_f64() / 1e6;
+    println!("{:<12} {:>10} positions  game {:>10.2?} ({:>6.2} M/s)  bitboard {:>10.2?} ({:>6.2} M/s)",
+             name, positions, game_time, per_second(game_time), bitboard_time, per_second(bitboard_time));
+}
+
+fn main() {
+    bench("3x3", TicTacToe::new(Rules::Classic), 9);
+    bench("4x4, k=3", TicTacToe::sized(Rules::Classic, 4, 4, 3), 5);
+    bench("cube 3", TicTacToe::cube(Rules::Classic, 3), 4);
+    bench("gravity 7x6", TicTacToe::sized(Rules::Classic, 7, 6, 4).with_gravity(true), 6);
+}
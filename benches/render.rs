@@ -0,0 +1,86 @@
+use std::io::Write;
+use std::time::Instant;
+
+use anyhow::Result;
+use crossterm::cursor::MoveTo;
+use crossterm::QueueableCommand;
+use crossterm::terminal::{Clear, ClearType};
+use tic_tac_toe::engine::rendering::frame;
+use tic_tac_toe::engine::rendering::frame::Frame;
+use tic_tac_toe::engine::rendering::renderer::Renderer;
+
+const WIDTH: usize = 50;
+const HEIGHT: usize = 50;
+const FRAMES: usize = 600;
+
+const GRID: [&str; 5] = [
+    "   ┃   ┃   ",
+    "━━━┃━━━┃━━━",
+    "   ┃   ┃   ",
+    "━━━┃━━━┃━━━",
+    "   ┃   ┃   ",
+];
+
+// The renderer as it was before batching: one cursor move per changed cell
+fn legacy_encode(buffer: &mut Vec<u8>, previous_frame: &Frame, next_frame: &Frame, force: bool) -> Result<()> {
+    if force {
+        buffer.queue(Clear(ClearType::All))?;
+    }
+    for (x, col) in next_frame.iter().enumerate() {
+        for (y, content) in col.iter().enumerate() {
+            if previous_frame[x][y] != *content || force {
+                buffer.queue(MoveTo(x as u16, y as u16))?;
+                write!(buffer, "{}", char::from_u32(*content).unwrap())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn draw_text(frame: &mut Frame, x: usize, y: usize, text: &str) {
+    for (i, char) in text.chars().enumerate() {
+        frame[x + i][y] = char as u32;
+    }
+}
+
+// A game screen whose status line, cursor and fps counter change from frame to frame
+fn game_frames() -> Vec<Frame> {
+    (0..FRAMES).map(|i| {
+        let mut frame = frame::new_frame(WIDTH, HEIGHT);
+        draw_text(&mut frame, 0, 0, &format!("Turn: Player {}", if (i / 60) % 2 == 0 { "ONE" } else { "TWO" }));
+        for (y, line) in GRID.iter().enumerate() {
+            draw_text(&mut frame, 0, 2 + y, line);
+        }
+        let cell = (i / 20) % 9;
+        draw_text(&mut frame, 1 + (cell % 3) * 4, 2 + (cell / 3) * 2, "x");
+        draw_text(&mut frame, 0, 8, "Q: Quit, Space: Play, Arrows: Move, F3: Debug");
+        draw_text(&mut frame, 0, 10, &format!("FPS: {}", 55 + i % 7));
+        frame
+    }).collect()
+}
+
+fn bench(name: &str, frames: &[Frame], encode: fn(&mut Vec<u8>, &Frame, &Frame, bool) -> Result<()>) -> Result<()> {
+    let mut buffer = Vec::new();
+    let mut bytes = 0;
+    let mut previous = frame::new_frame(WIDTH, HEIGHT);
+    let start = Instant::now();
+
+    for (i, next) in frames.iter().enumerate() {
+        buffer.clear();
+        encode(&mut buffer, &previous, next, i == 0)?;
+        bytes += buffer.len();
+        previous = next.clone();
+    }
+
+    let elapsed = start.elapsed();
+    println!("{:<8} {:>8} bytes/frame {:>10.2?}/frame",
+             name, bytes / frames.len(), elapsed / frames.len() as u32);
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let frames = game_frames();
+    bench("legacy", &frames, legacy_encode)?;
+    bench("batched", &frames, Renderer::encode)?;
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use std::time::Instant;
+
+use tic_tac_toe::ai::analysis::analyse;
+use tic_tac_toe::tictactoe::game::TicTacToe;
+use tic_tac_toe::tictactoe::moves::Move;
+use tic_tac_toe::tictactoe::rules::Rules;
+use tic_tac_toe::tictactoe::solver::{reachable_positions, Solver};
+
+fn solve(name: &str, game: TicTacToe) {
+    let mut solver = Solver::new(&game).expect("board fits in 64 cells");
+    let start = Instant::now();
+    let evaluation = solver.evaluate(&game);
+    println!("{:<14} {:?} in {} moves, {:>8} positions stored, {:>10.2?}",
+             name, evaluation.outcome, evaluation.distance, solver.table_size(), start.elapsed());
+}
+
+fn main() {
+    let start = Instant::now();
+    let positions = reachable_positions(&TicTacToe::new(Rules::Classic)).expect("board fits in 64 cells");
+    println!("{:<14} {} reachable positions, {:>10.2?}", "3x3", positions, start.elapsed());
+
+    solve("classic", TicTacToe::new(Rules::Classic));
+    solve("misere", TicTacToe::new(Rules::Misere));
+    solve("wild", TicTacToe::new(Rules::Wild));
+    // A full game with one blunder in it
+    let mut game = TicTacToe::new(Rules::Classic);
+    for notation in ["b2", "b1", "a1", "c3", "a3", "a2", "c1"] {
+        game.play_move(&Move::from_notation(notation, &game).expect("valid cell")).expect("legal move");
+    }
+    let start = Instant::now();
+    let analysis = analyse(&game);
+    println!("{:<14} {} moves graded, {:>10.2?}", "analysis", analysis.moves.len(), start.elapsed());
+
+    solve("4x4, k=3", TicTacToe::sized(Rules::Classic, 4, 4, 3));
+    solve("gravity 4x4", TicTacToe::sized(Rules::Classic, 4, 4, 3).with_gravity(true));
+}
@@ -0,0 +1,160 @@
+use crate::ai::computer::{affordable_depth, SOLVER_MAX_CELLS};
+use crate::ai::search;
+use crate::tictactoe::game::TicTacToe;
+use crate::tictactoe::moves::{GameStatus, Move};
+use crate::tictactoe::player::Player;
+use crate::tictactoe::solver::{Outcome, Solver};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    // Kept the best result the position allowed
+    Best,
+    // Let a win slip into a draw
+    Inaccuracy,
+    // Turned a position that could be held into a loss
+    Blunder,
+}
+
+#[derive(Debug, Clone)]
+pub struct MoveAnalysis {
+    pub mv: Move,
+    pub player: Player,
+    pub verdict: Verdict,
+    // For the player who moved, with the best move and with the one played
+    pub best_outcome: Outcome,
+    pub played_outcome: Outcome,
+    // Every move that would have kept the best outcome
+    pub best_moves: Vec<Move>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Analysis {
+    pub moves: Vec<MoveAnalysis>,
+    // Solved perfectly, boards too big for the solver get a search that can be wrong
+    pub exact: bool,
+    // The move the game was lost with, None for draws and games lost from the start
+    pub turning_point: Option<usize>,
+}
+
+// Replays the game's moves from the empty board and grades each one
+pub fn analyse(game: &TicTacToe) -> Analysis {
+    analyse_until(game, &|| false).expect("never stopped")
+}
+
+// Like analyse, but None once `should_stop` returns true before every move is graded
+pub fn analyse_until(game: &TicTacToe, should_stop: &dyn Fn() -> bool) -> Option<Analysis> {
+    let mut position = game.clone();
+    while position.undo().is_some() {}
+
+    let board = position.state();
+    let mut solver = if board.width() * board.height() * board.depth() <= SOLVER_MAX_CELLS {
+        Solver::new(&position)
+    } else {
+        None
+    };
+    let exact = solver.is_some();
+
+    let mut moves = vec![];
+    for mv in game.history() {
+        let outcomes: Vec<(Move, Outcome)> = match solver.as_mut() {
+            Some(solver) => solver.evaluate_moves_until(&position, should_stop)?.into_iter()
+                .map(|(mv, evaluation)| (mv, evaluation.outcome))
+                .collect(),
+            None => search::score_moves_until(&position, affordable_depth(position.legal_moves().len()), should_stop)?
+                .into_iter()
+                .map(|(mv, score)| (mv, outcome_of(score)))
+                .collect(),
+        };
+
+        let best_outcome = outcomes.iter()
+            .map(|(_, outcome)| *outcome)
+            .max_by_key(rank)
+            .unwrap_or(Outcome::Draw);
+        let played_outcome = outcomes.iter()
+            .find(|(other, _)| other == mv)
+            .map_or(best_outcome, |(_, outcome)| *outcome);
+        let verdict = match played_outcome {
+            outcome if outcome == best_outcome => Verdict::Best,
+            Outcome::Loss => Verdict::Blunder,
+            _ => Verdict::Inaccuracy,
+        };
+        let best_moves = outcomes.into_iter()
+            .filter(|(_, outcome)| *outcome == best_outcome)
+            .map(|(mv, _)| mv)
+            .collect();
+
+        moves.push(MoveAnalysis {
+            mv: mv.clone(),
+            player: position.turn().clone(),
+            verdict,
+            best_outcome,
+            played_outcome,
+            best_moves,
+        });
+        position.play_move(mv).expect("recorded moves are legal");
+    }
+
+    // The loser's last blunder, the opponent never let them back after it
+    let turning_point = match game.status() {
+        GameStatus::Won(winner) => moves.iter()
+            .rposition(|analysis| analysis.player != winner && analysis.verdict == Verdict::Blunder),
+        _ => None,
+    };
+
+    Some(Analysis { moves, exact, turning_point })
+}
+
+fn rank(outcome: &Outcome) -> u8 {
+    match outcome {
+        Outcome::Loss => 0,
+        Outcome::Draw => 1,
+        Outcome::Win => 2,
+    }
+}
+
+// Search scores are for the player who moved, anything short of a forced result counts as a draw
+fn outcome_of(score: i32) -> Outcome {
+    match score {
+        score if score > 0 => Outcome::Win,
+        score if score < 0 => Outcome::Loss,
+        _ => Outcome::Draw,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tictactoe::game::played;
+    use crate::tictactoe::rules::Rules;
+
+    #[test]
+    fn answering_the_center_with_an_edge_is_the_blunder() {
+        let analysis = analyse(&played(Rules::Classic, &["b2", "b1", "a1", "c3", "a3", "a2", "c1"]));
+        let verdicts: Vec<Verdict> = analysis.moves.iter().map(|graded| graded.verdict).collect();
+        assert!(analysis.exact);
+        assert_eq!(verdicts[1], Verdict::Blunder);
+        assert!(verdicts.iter().enumerate().all(|(ply, verdict)| ply == 1 || *verdict == Verdict::Best));
+        assert_eq!(analysis.turning_point, Some(1));
+    }
+
+    #[test]
+    fn a_draw_has_no_turning_point() {
+        let analysis = analyse(&played(Rules::Classic, &["b2", "a1", "c1", "a3", "a2", "c2", "b1", "b3", "c3"]));
+        assert!(analysis.moves.iter().all(|graded| graded.verdict == Verdict::Best));
+        assert_eq!(analysis.turning_point, None);
+    }
+
+    #[test]
+    fn letting_a_win_slip_is_an_inaccuracy() {
+        // After O's edge reply X can fork with a3, c1 only threatens a single line
+        let analysis = analyse(&played(Rules::Classic, &["a1", "b1", "b2", "c3", "c1"]));
+        let last = analysis.moves.last().unwrap();
+        assert_eq!(last.verdict, Verdict::Inaccuracy);
+        assert_eq!((last.best_outcome, last.played_outcome), (Outcome::Win, Outcome::Draw));
+    }
+
+    #[test]
+    fn a_stopped_analysis_gives_up() {
+        assert!(analyse_until(&played(Rules::Classic, &["b2", "b1"]), &|| true).is_none());
+    }
+}
@@ -0,0 +1,161 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::ai::{search, Ai};
+use crate::tictactoe::game::TicTacToe;
+use crate::tictactoe::moves::Move;
+use crate::tictactoe::solver::Solver;
+
+pub const EASY_BLUNDER_PROBABILITY: f64 = 0.3;
+const MEDIUM_DEPTH: usize = 2;
+// The solver plays boards up to 4x4 perfectly, bigger ones get a search as deep as this allows
+pub(crate) const SOLVER_MAX_CELLS: usize = 16;
+const SEARCH_NODE_BUDGET: usize = 200_000;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum Difficulty {
+    // Any legal move
+    Random,
+    // Medium, but blunders into a random move now and then
+    Easy,
+    // Looks two moves ahead
+    #[default]
+    Medium,
+    // Perfect play where the solver can manage, deep search otherwise
+    Hard,
+}
+
+impl FromStr for Difficulty {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_lowercase().as_str() {
+            "random" => Ok(Difficulty::Random),
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(anyhow!("Unknown difficulty '{}', expected random, easy, medium or hard.", name)),
+        }
+    }
+}
+
+// Picks at random among the moves it likes best, so games don't repeat
+// unless the seed does.
+pub struct ComputerPlayer {
+    difficulty: Difficulty,
+    blunder_probability: f64,
+    rng: StdRng,
+    solver: Option<Solver>,
+}
+
+impl ComputerPlayer {
+    pub fn new(difficulty: Difficulty) -> Self {
+        Self {
+            difficulty,
+            blunder_probability: EASY_BLUNDER_PROBABILITY,
+            rng: StdRng::from_entropy(),
+            solver: None,
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    // How often Easy plays a random move
+    pub fn with_blunder_probability(mut self, probability: f64) -> Self {
+        self.blunder_probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    fn perfect_moves(&mut self, game: &TicTacToe, should_stop: &dyn Fn() -> bool) -> Vec<Move> {
+        let board = game.state();
+        if board.width() * board.height() * board.depth() <= SOLVER_MAX_CELLS {
+            if !self.solver.as_ref().is_some_and(|solver| solver.layout().matches(game)) {
+                self.solver = Solver::new(game);
+            }
+            if let Some(solver) = self.solver.as_mut() {
+                // A short search is ready in case the solver runs out of time
+                let fallback = deepening_search(game, MEDIUM_DEPTH, should_stop);
+                return solver.best_moves_until(game, should_stop).unwrap_or(fallback);
+            }
+        }
+
+        deepening_search(game, affordable_depth(game.legal_moves().len()), should_stop)
+    }
+}
+
+impl Ai for ComputerPlayer {
+    fn name(&self) -> String {
+        format!("{:?} computer", self.difficulty)
+    }
+
+    fn choose_move(&mut self, game: &TicTacToe) -> Option<Move> {
+        self.choose_move_until(game, &|| false)
+    }
+
+    fn choose_move_until(&mut self, game: &TicTacToe, should_stop: &dyn Fn() -> bool) -> Option<Move> {
+        let moves = match self.difficulty {
+            Difficulty::Random => game.legal_moves(),
+            Difficulty::Easy if self.rng.gen_bool(self.blunder_probability) => game.legal_moves(),
+            Difficulty::Easy | Difficulty::Medium => deepening_search(game, MEDIUM_DEPTH, should_stop),
+            Difficulty::Hard => self.perfect_moves(game, should_stop),
+        };
+        moves.choose(&mut self.rng).cloned()
+    }
+}
+
+// The best moves of the deepest search up to `depth` that finished before `should_stop`.
+// Looking a single move ahead always finishes, so there is a move to play whenever one is legal.
+fn deepening_search(game: &TicTacToe, depth: usize, should_stop: &dyn Fn() -> bool) -> Vec<Move> {
+    let mut moves = search::best_moves(game, 1);
+    for depth in 2..=depth {
+        match search::best_moves_until(game, depth, should_stop) {
+            Some(deeper) => moves = deeper,
+            None => break,
+        }
+    }
+    moves
+}
+
+// Deepest search whose game tree stays within the node budget
+pub(crate) fn affordable_depth(moves: usize) -> usize {
+    let mut depth = MEDIUM_DEPTH;
+    while depth < moves && moves.saturating_pow(depth as u32 + 1) <= SEARCH_NODE_BUDGET {
+        depth += 1;
+    }
+    depth
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tictactoe::game::played_on;
+    use crate::tictactoe::rules::Rules;
+
+    #[test]
+    fn stopped_searches_still_play_a_legal_move() {
+        for game in [TicTacToe::sized(Rules::Classic, 4, 4, 3), TicTacToe::sized(Rules::Classic, 9, 9, 5)] {
+            for difficulty in [Difficulty::Medium, Difficulty::Hard] {
+                let mv = ComputerPlayer::new(difficulty).with_seed(1).choose_move_until(&game, &|| true);
+                assert!(game.legal_moves().contains(&mv.unwrap()), "{:?}", difficulty);
+            }
+        }
+    }
+
+    #[test]
+    fn stopped_searches_still_take_a_win() {
+        let game = played_on(TicTacToe::sized(Rules::Classic, 4, 4, 3), &["a1", "b1", "a2", "b2"]);
+        let mv = ComputerPlayer::new(Difficulty::Hard).choose_move_until(&game, &|| true).unwrap();
+        assert!(["a3", "b3"].contains(&mv.coords.to_notation().as_str()));
+    }
+}
@@ -0,0 +1,222 @@
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::ai::Ai;
+use crate::tictactoe::game::TicTacToe;
+use crate::tictactoe::moves::{GameStatus, Move};
+use crate::tictactoe::player::Player;
+
+pub const DEFAULT_ITERATIONS: usize = 20_000;
+// What the game gives it per move, big boards never get near the iterations in that time
+pub const DEFAULT_TIME_LIMIT: Duration = Duration::from_secs(1);
+// The textbook UCT constant, sqrt(2)
+pub const DEFAULT_EXPLORATION: f64 = std::f64::consts::SQRT_2;
+// How often the budget and cancellation are checked
+const ITERATIONS_BETWEEN_CHECKS: usize = 64;
+
+struct Node {
+    // None at the root
+    mv: Option<Move>,
+    // Whoever played `mv`, the results stored here are theirs
+    mover: Player,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<Move>,
+    visits: u32,
+    // Wins count 1 and draws 0.5
+    score: f64,
+}
+
+// A search tree grown a few iterations at a time, so it can be spread over several frames
+pub struct MctsSearch {
+    root: TicTacToe,
+    nodes: Vec<Node>,
+    exploration: f64,
+    iterations: usize,
+}
+
+impl MctsSearch {
+    pub fn new(game: &TicTacToe, exploration: f64) -> Self {
+        let root = Node {
+            mv: None,
+            mover: game.turn().other(),
+            parent: None,
+            children: vec![],
+            untried: game.legal_moves(),
+            visits: 0,
+            score: 0.0,
+        };
+
+        Self {
+            root: game.clone(),
+            nodes: vec![root],
+            exploration,
+            iterations: 0,
+        }
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    pub fn run(&mut self, iterations: usize, rng: &mut StdRng) {
+        for _ in 0..iterations {
+            self.iterate(rng);
+        }
+    }
+
+    // The most visited move, which is the one the search trusts most
+    pub fn best_move(&self) -> Option<Move> {
+        self.nodes[0].children.iter()
+            .max_by_key(|child| self.nodes[**child].visits)
+            .and_then(|child| self.nodes[*child].mv.clone())
+            .or_else(|| self.nodes[0].untried.first().cloned())
+    }
+
+    fn iterate(&mut self, rng: &mut StdRng) {
+        let mut game = self.root.clone();
+        let mut node = 0;
+
+        // Selection: follow the most promising children down to a node with moves left to try
+        while self.nodes[node].untried.is_empty() && !self.nodes[node].children.is_empty() {
+            node = self.select_child(node);
+            let mv = self.nodes[node].mv.as_ref().expect("only the root has no move");
+            game.play_move(mv).expect("legal move");
+        }
+
+        // Expansion: add one of the untried moves
+        if !self.nodes[node].untried.is_empty() {
+            let untried = &mut self.nodes[node].untried;
+            let mv = untried.swap_remove(rng.gen_range(0..untried.len()));
+            let mover = game.turn().clone();
+            game.play_move(&mv).expect("legal move");
+
+            let child = Node {
+                mv: Some(mv),
+                mover,
+                parent: Some(node),
+                children: vec![],
+                untried: game.legal_moves(),
+                visits: 0,
+                score: 0.0,
+            };
+            self.nodes.push(child);
+            let child = self.nodes.len() - 1;
+            self.nodes[node].children.push(child);
+            node = child;
+        }
+
+        // Simulation: random moves until the game ends
+        while game.status() == GameStatus::InProgress {
+            let mv = game.legal_moves().choose(rng).cloned().expect("game in progress");
+            game.play_move(&mv).expect("legal move");
+        }
+
+        // Backpropagation
+        let winner = match game.status() {
+            GameStatus::Won(winner) => Some(winner),
+            _ => None,
+        };
+        let mut current = Some(node);
+        while let Some(index) = current {
+            let node = &mut self.nodes[index];
+            node.visits += 1;
+            node.score += match &winner {
+                Some(winner) if *winner == node.mover => 1.0,
+                Some(_) => 0.0,
+                None => 0.5,
+            };
+            current = node.parent;
+        }
+        self.iterations += 1;
+    }
+
+    // Upper confidence bound applied to trees
+    fn select_child(&self, node: usize) -> usize {
+        let parent_visits = (self.nodes[node].visits as f64).ln();
+        let uct = |child: usize| {
+            let child = &self.nodes[child];
+            let visits = child.visits as f64;
+            child.score / visits + self.exploration * (parent_visits / visits).sqrt()
+        };
+
+        *self.nodes[node].children.iter()
+            .max_by(|a, b| uct(**a).total_cmp(&uct(**b)))
+            .expect("node has children")
+    }
+}
+
+// Monte Carlo tree search, for boards far too big to search exhaustively. It stops thinking
+// once it has run its iterations or used up its time limit, whichever comes first.
+pub struct MctsPlayer {
+    iterations: usize,
+    time_limit: Option<Duration>,
+    exploration: f64,
+    rng: StdRng,
+}
+
+impl Default for MctsPlayer {
+    fn default() -> Self {
+        MctsPlayer::new()
+    }
+}
+
+impl MctsPlayer {
+    pub fn new() -> Self {
+        Self {
+            iterations: DEFAULT_ITERATIONS,
+            time_limit: None,
+            exploration: DEFAULT_EXPLORATION,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations.max(1);
+        self
+    }
+
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    // Higher values try more moves, lower values dig deeper into the good ones
+    pub fn with_exploration(mut self, exploration: f64) -> Self {
+        self.exploration = exploration;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    fn is_done(&self, search: &MctsSearch, elapsed: Duration) -> bool {
+        search.iterations() >= self.iterations
+            || self.time_limit.is_some_and(|limit| elapsed >= limit)
+    }
+}
+
+impl Ai for MctsPlayer {
+    fn name(&self) -> String {
+        format!("MCTS ({} iterations)", self.iterations)
+    }
+
+    fn choose_move(&mut self, game: &TicTacToe) -> Option<Move> {
+        self.choose_move_until(game, &|| false)
+    }
+
+    fn choose_move_until(&mut self, game: &TicTacToe, should_stop: &dyn Fn() -> bool) -> Option<Move> {
+        let mut search = MctsSearch::new(game, self.exploration);
+        let start = Instant::now();
+        while !self.is_done(&search, start.elapsed()) && !should_stop() {
+            let batch = ITERATIONS_BETWEEN_CHECKS.min(self.iterations - search.iterations());
+            search.run(batch, &mut self.rng);
+        }
+        search.best_move()
+    }
+}
@@ -0,0 +1,21 @@
+use crate::tictactoe::game::TicTacToe;
+use crate::tictactoe::moves::Move;
+
+pub mod analysis;
+pub mod computer;
+pub mod mcts;
+pub mod search;
+
+// Anything that can pick a move for the player whose turn it is
+pub trait Ai: Send {
+    fn name(&self) -> String;
+
+    // None once the game is over
+    fn choose_move(&mut self, game: &TicTacToe) -> Option<Move>;
+
+    // Like choose_move, but gives up early with its best guess so far once `should_stop`
+    // returns true. Players that decide quickly never check it.
+    fn choose_move_until(&mut self, game: &TicTacToe, _should_stop: &dyn Fn() -> bool) -> Option<Move> {
+        self.choose_move(game)
+    }
+}
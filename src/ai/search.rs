@@ -0,0 +1,65 @@
+use crate::tictactoe::game::TicTacToe;
+use crate::tictactoe::moves::{GameStatus, Move};
+
+const WIN_SCORE: i32 = 1000;
+
+// Score of every legal move for the player making it, looking `depth` moves ahead
+pub fn score_moves(game: &TicTacToe, depth: usize) -> Vec<(Move, i32)> {
+    score_moves_until(game, depth, &|| false).expect("never stopped")
+}
+
+// Like score_moves, but None once `should_stop` returns true before every move is scored
+pub fn score_moves_until(game: &TicTacToe, depth: usize, should_stop: &dyn Fn() -> bool) -> Option<Vec<(Move, i32)>> {
+    game.legal_moves().into_iter()
+        .map(|mv| {
+            let score = -negamax_until(&after(game, &mv), depth.saturating_sub(1), should_stop)?;
+            Some((mv, score))
+        })
+        .collect()
+}
+
+// The moves that score best, so callers can pick among them
+pub fn best_moves(game: &TicTacToe, depth: usize) -> Vec<Move> {
+    best_moves_until(game, depth, &|| false).expect("never stopped")
+}
+
+pub fn best_moves_until(game: &TicTacToe, depth: usize, should_stop: &dyn Fn() -> bool) -> Option<Vec<Move>> {
+    let moves = score_moves_until(game, depth, should_stop)?;
+    let best = moves.iter().map(|(_, score)| *score).max();
+    Some(moves.into_iter()
+        .filter(|(_, score)| Some(*score) == best)
+        .map(|(mv, _)| mv)
+        .collect())
+}
+
+// Score for the player to move. Positions still open at the horizon count as draws, and
+// every move spent shrinks a result so quick wins and slow losses are preferred.
+pub fn negamax(game: &TicTacToe, depth: usize) -> i32 {
+    negamax_until(game, depth, &|| false).expect("never stopped")
+}
+
+// None once `should_stop` returns true, a search cut short has no score
+pub fn negamax_until(game: &TicTacToe, depth: usize, should_stop: &dyn Fn() -> bool) -> Option<i32> {
+    match game.status() {
+        GameStatus::Won(winner) if winner == *game.turn() => return Some(WIN_SCORE),
+        GameStatus::Won(_) => return Some(-WIN_SCORE),
+        GameStatus::Draw => return Some(0),
+        GameStatus::InProgress if depth == 0 => return Some(0),
+        GameStatus::InProgress => {}
+    }
+    if should_stop() {
+        return None;
+    }
+
+    let scores = game.legal_moves().iter()
+        .map(|mv| negamax_until(&after(game, mv), depth - 1, should_stop).map(|score| -score))
+        .collect::<Option<Vec<i32>>>()?;
+    let score = scores.into_iter().max().unwrap_or(0);
+    Some(score - score.signum())
+}
+
+fn after(game: &TicTacToe, mv: &Move) -> TicTacToe {
+    let mut next = game.clone();
+    next.play_move(mv).expect("legal move");
+    next
+}
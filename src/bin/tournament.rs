@@ -0,0 +1,390 @@
+use std::env;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error, Result};
+use tic_tac_toe::ai::Ai;
+use tic_tac_toe::ai::computer::{ComputerPlayer, Difficulty};
+use tic_tac_toe::ai::mcts::MctsPlayer;
+use tic_tac_toe::tictactoe::game::{BoardSize, TicTacToe};
+use tic_tac_toe::tictactoe::moves::GameStatus;
+use tic_tac_toe::tictactoe::player::Player;
+use tic_tac_toe::tictactoe::rules::Rules;
+
+const USAGE: &str = "Usage: tournament --players PLAYER,PLAYER[,...] [--games N] [--format csv|json] [--seed N]
+                  [--rules classic|misere|wild|notakto] [--gravity] [--width N] [--height N] [--k N]
+Players: random, easy[:BLUNDER_PROBABILITY], medium, hard, mcts[:ITERATIONS]";
+
+const DEFAULT_GAMES: usize = 100;
+const AVERAGE_RATING: f64 = 1500.0;
+const RATING_ITERATIONS: usize = 1000;
+// Ratings stop moving by more than this once they have settled
+const RATING_PRECISION: f64 = 0.001;
+// Margins cover 95% of the likely differences
+const CONFIDENCE: f64 = 1.96;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    // With Easy's blunder probability when given
+    Computer(Difficulty, Option<f64>),
+    // With the number of iterations when given
+    Mcts(Option<usize>),
+}
+
+// A player as named on the command line, e.g. "hard", "easy:0.1" or "mcts:5000"
+#[derive(Debug, Clone, PartialEq)]
+struct PlayerSpec {
+    name: String,
+    kind: Kind,
+}
+
+impl FromStr for PlayerSpec {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let name = name.trim().to_lowercase();
+        let (player, setting) = match name.split_once(':') {
+            Some((player, setting)) => (player, Some(setting)),
+            None => (name.as_str(), None),
+        };
+        let invalid = || anyhow!("Can't make sense of player '{}'.\n{}", name, USAGE);
+
+        let kind = match (player, setting) {
+            ("mcts", None) => Kind::Mcts(None),
+            ("mcts", Some(iterations)) => Kind::Mcts(Some(iterations.parse().map_err(|_| invalid())?)),
+            ("easy", Some(probability)) => {
+                Kind::Computer(Difficulty::Easy, Some(probability.parse().map_err(|_| invalid())?))
+            }
+            (difficulty, None) => Kind::Computer(difficulty.parse()?, None),
+            _ => return Err(invalid()),
+        };
+        Ok(Self { name, kind })
+    }
+}
+
+impl PlayerSpec {
+    fn create(&self, seed: Option<u64>) -> Box<dyn Ai> {
+        match self.kind {
+            Kind::Computer(difficulty, probability) => {
+                let mut player = ComputerPlayer::new(difficulty);
+                if let Some(probability) = probability {
+                    player = player.with_blunder_probability(probability);
+                }
+                if let Some(seed) = seed {
+                    player = player.with_seed(seed);
+                }
+                Box::new(player)
+            }
+            Kind::Mcts(iterations) => {
+                let mut player = MctsPlayer::new();
+                if let Some(iterations) = iterations {
+                    player = player.with_iterations(iterations);
+                }
+                if let Some(seed) = seed {
+                    player = player.with_seed(seed);
+                }
+                Box::new(player)
+            }
+        }
+    }
+}
+
+struct Options {
+    players: Vec<PlayerSpec>,
+    // Per pairing
+    games: usize,
+    format: Format,
+    seed: Option<u64>,
+    rules: Rules,
+    gravity: bool,
+    board: BoardSize,
+}
+
+impl Options {
+    fn from_args() -> Result<Self> {
+        let mut options = Options {
+            players: vec![],
+            games: DEFAULT_GAMES,
+            format: Format::Csv,
+            seed: None,
+            rules: Rules::default(),
+            gravity: false,
+            board: BoardSize::default(),
+        };
+        let mut args = env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| anyhow!("Missing value for {}.\n{}", arg, USAGE));
+            match arg.as_str() {
+                "--players" => {
+                    options.players = value()?.split(',')
+                        .map(|player| player.parse())
+                        .collect::<Result<_>>()?;
+                }
+                "--games" => {
+                    options.games = Options::parse_number(&arg, value()?)?;
+                    if options.games == 0 {
+                        return Err(anyhow!("--games expects at least one game.\n{}", USAGE));
+                    }
+                }
+                "--format" => {
+                    options.format = match value()?.as_str() {
+                        "csv" => Format::Csv,
+                        "json" => Format::Json,
+                        format => return Err(anyhow!("Unknown format '{}'.\n{}", format, USAGE)),
+                    };
+                }
+                "--seed" => options.seed = Some(Options::parse_number(&arg, value()?)? as u64),
+                "--rules" => options.rules = value()?.parse()?,
+                "--gravity" => options.gravity = true,
+                "--width" => options.board.width = Some(Options::parse_size(&arg, value()?)?),
+                "--height" => options.board.height = Some(Options::parse_size(&arg, value()?)?),
+                "--k" => options.board.line_length = Some(Options::parse_size(&arg, value()?)?),
+                "--help" | "-h" => return Err(anyhow!(USAGE)),
+                _ => return Err(anyhow!("Unknown argument '{}'.\n{}", arg, USAGE)),
+            }
+        }
+
+        if options.players.len() < 2 {
+            return Err(anyhow!("A tournament needs at least two players.\n{}", USAGE));
+        }
+        for (index, player) in options.players.iter().enumerate() {
+            if options.players[..index].iter().any(|other| other.name == player.name) {
+                return Err(anyhow!("Player '{}' is entered twice.", player.name));
+            }
+        }
+        options.board.check(options.gravity)?;
+
+        Ok(options)
+    }
+
+    fn parse_number(arg: &str, value: String) -> Result<usize> {
+        value.parse().map_err(|_| anyhow!("{} expects a number.\n{}", arg, USAGE))
+    }
+
+    fn parse_size(arg: &str, value: String) -> Result<usize> {
+        BoardSize::parse(arg, &value).map_err(|error| anyhow!("{}\n{}", error, USAGE))
+    }
+
+    fn new_game(&self) -> TicTacToe {
+        self.board.new_game(self.rules, self.gravity)
+    }
+}
+
+// Results from one player's side
+#[derive(Default, Debug, Clone, Copy)]
+struct Record {
+    wins: usize,
+    draws: usize,
+    losses: usize,
+}
+
+impl Record {
+    fn games(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+
+    fn points(&self) -> f64 {
+        self.wins as f64 + self.draws as f64 / 2.0
+    }
+
+    fn score(&self) -> f64 {
+        self.points() / self.games().max(1) as f64
+    }
+
+    fn reversed(&self) -> Record {
+        Record { wins: self.losses, draws: self.draws, losses: self.wins }
+    }
+
+    fn add(&mut self, other: &Record) {
+        self.wins += other.wins;
+        self.draws += other.draws;
+        self.losses += other.losses;
+    }
+
+    // How much stronger the player looks, and the margin around it. An extra draw keeps
+    // perfect scores from meaning an infinite difference.
+    fn elo_difference(&self) -> (f64, f64) {
+        let games = self.games() as f64 + 1.0;
+        let score = (self.points() + 0.5) / games;
+        let variance = [(self.wins, 1.0), (self.draws, 0.5), (self.losses, 0.0), (1, 0.5)].iter()
+            .map(|(count, points)| *count as f64 * (points - score).powi(2))
+            .sum::<f64>() / games;
+        let error = (variance / games).sqrt();
+        // How fast the rating difference changes with the score
+        let slope = 400.0 / (10f64.ln() * score * (1.0 - score));
+        (elo(score), CONFIDENCE * error * slope)
+    }
+}
+
+// The rating difference at which a player is expected to score `score`
+fn elo(score: f64) -> f64 {
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+fn expected_score(difference: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-difference / 400.0))
+}
+
+struct Pairing {
+    first: usize,
+    second: usize,
+    // From the first player's side
+    record: Record,
+}
+
+// Plays until the game is over and says how it ended
+fn play_game(mut game: TicTacToe, x: &mut dyn Ai, o: &mut dyn Ai) -> Result<GameStatus> {
+    while game.status() == GameStatus::InProgress {
+        let player: &mut dyn Ai = if *game.turn() == Player::ONE { &mut *x } else { &mut *o };
+        let mv = player.choose_move(&game).ok_or_else(|| anyhow!("{} found no move.", player.name()))?;
+        game.play_move(&mv).map_err(|error| anyhow!("{} played {}: {}", player.name(), mv.to_notation(), error))?;
+    }
+    Ok(game.status())
+}
+
+// `first` plays X in the even games and O in the odd ones
+fn play_pairing(options: &Options, first: &mut dyn Ai, second: &mut dyn Ai) -> Result<Record> {
+    let mut record = Record::default();
+    for round in 0..options.games {
+        let first_side = if round % 2 == 0 { Player::ONE } else { Player::TWO };
+        let status = if first_side == Player::ONE {
+            play_game(options.new_game(), first, second)?
+        } else {
+            play_game(options.new_game(), second, first)?
+        };
+        match status {
+            GameStatus::Won(winner) if winner == first_side => record.wins += 1,
+            GameStatus::Won(_) => record.losses += 1,
+            _ => record.draws += 1,
+        }
+    }
+    Ok(record)
+}
+
+// The ratings that best explain every pairing's score, averaging AVERAGE_RATING. Each pairing
+// counts an extra draw, like Record::elo_difference.
+fn ratings(players: usize, pairings: &[Pairing]) -> Vec<f64> {
+    let mut ratings = vec![AVERAGE_RATING; players];
+    for _ in 0..RATING_ITERATIONS {
+        let mut largest_change: f64 = 0.0;
+        for player in 0..players {
+            let (mut surplus, mut sensitivity) = (0.0, 0.0);
+            for pairing in pairings {
+                let (record, opponent) = if pairing.first == player {
+                    (pairing.record, pairing.second)
+                } else if pairing.second == player {
+                    (pairing.record.reversed(), pairing.first)
+                } else {
+                    continue;
+                };
+                let games = record.games() as f64 + 1.0;
+                let expected = expected_score(ratings[player] - ratings[opponent]);
+                surplus += record.points() + 0.5 - games * expected;
+                sensitivity += games * expected * (1.0 - expected);
+            }
+            // A Newton step towards the rating where points and expected points match
+            let change = surplus / sensitivity.max(f64::EPSILON) * 400.0 / 10f64.ln();
+            ratings[player] += change;
+            largest_change = largest_change.max(change.abs());
+        }
+        if largest_change < RATING_PRECISION {
+            break;
+        }
+    }
+
+    let shift = AVERAGE_RATING - ratings.iter().sum::<f64>() / players as f64;
+    ratings.iter().map(|rating| rating + shift).collect()
+}
+
+fn json_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn print_csv(players: &[PlayerSpec], pairings: &[Pairing], totals: &[Record], ratings: &[f64]) {
+    println!("player,opponent,games,wins,draws,losses,score,elo_difference,margin");
+    for pairing in pairings {
+        let record = pairing.record;
+        let (difference, margin) = record.elo_difference();
+        println!("{},{},{},{},{},{},{:.3},{:.1},{:.1}",
+                 players[pairing.first].name, players[pairing.second].name, record.games(),
+                 record.wins, record.draws, record.losses, record.score(), difference, margin);
+    }
+
+    println!();
+    println!("player,games,wins,draws,losses,score,elo");
+    for (index, player) in players.iter().enumerate() {
+        let record = totals[index];
+        println!("{},{},{},{},{},{:.3},{:.1}",
+                 player.name, record.games(), record.wins, record.draws, record.losses, record.score(), ratings[index]);
+    }
+}
+
+fn print_json(players: &[PlayerSpec], pairings: &[Pairing], totals: &[Record], ratings: &[f64]) {
+    let pairings: Vec<String> = pairings.iter()
+        .map(|pairing| {
+            let record = pairing.record;
+            let (difference, margin) = record.elo_difference();
+            format!("    {{\"player\": {}, \"opponent\": {}, \"games\": {}, \"wins\": {}, \"draws\": {}, \"losses\": {}, \
+                     \"score\": {:.3}, \"elo_difference\": {:.1}, \"margin\": {:.1}}}",
+                    json_string(&players[pairing.first].name), json_string(&players[pairing.second].name),
+                    record.games(), record.wins, record.draws, record.losses, record.score(), difference, margin)
+        })
+        .collect();
+    let ratings: Vec<String> = players.iter().enumerate()
+        .map(|(index, player)| {
+            let record = totals[index];
+            format!("    {{\"player\": {}, \"games\": {}, \"wins\": {}, \"draws\": {}, \"losses\": {}, \
+                     \"score\": {:.3}, \"elo\": {:.1}}}",
+                    json_string(&player.name), record.games(), record.wins, record.draws, record.losses,
+                    record.score(), ratings[index])
+        })
+        .collect();
+
+    println!("{{");
+    println!("  \"pairings\": [\n{}\n  ],", pairings.join(",\n"));
+    println!("  \"ratings\": [\n{}\n  ]", ratings.join(",\n"));
+    println!("}}");
+}
+
+// FNV-1a, which unlike the standard hasher is the same from one build to the next
+fn name_hash(name: &str) -> u64 {
+    name.bytes().fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+// Every player meets every other one, without the terminal UI
+fn main() -> Result<()> {
+    let options = Options::from_args()?;
+    let players = &options.players;
+
+    let mut pairings = vec![];
+    for first in 0..players.len() {
+        for second in first + 1..players.len() {
+            // Seeds follow the names of the pair, so adding a player doesn't change the other games
+            let pairing = name_hash(&players[first].name).wrapping_mul(31).wrapping_add(name_hash(&players[second].name));
+            let seed = |side: u64| options.seed.map(|seed| seed.wrapping_add(pairing).wrapping_add(side));
+            let mut first_ai = players[first].create(seed(0));
+            let mut second_ai = players[second].create(seed(1));
+            let record = play_pairing(&options, first_ai.as_mut(), second_ai.as_mut())?;
+            pairings.push(Pairing { first, second, record });
+        }
+    }
+
+    let mut totals = vec![Record::default(); players.len()];
+    for pairing in &pairings {
+        totals[pairing.first].add(&pairing.record);
+        totals[pairing.second].add(&pairing.record.reversed());
+    }
+    let ratings = ratings(players.len(), &pairings);
+
+    match options.format {
+        Format::Csv => print_csv(players, &pairings, &totals, &ratings),
+        Format::Json => print_json(players, &pairings, &totals, &ratings),
+    }
+    Ok(())
+}
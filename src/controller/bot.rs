@@ -0,0 +1,145 @@
+use std::io::Write;
+use std::process::{Child, ChildStdin, Command as Process, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use crossbeam_channel::{Receiver, RecvTimeoutError, TryRecvError};
+
+use crate::controller::{spawn_line_reader, Controller, HANDSHAKE_TIMEOUT};
+use crate::protocol::{Command, GameSettings, Reply};
+use crate::tictactoe::game::TicTacToe;
+use crate::tictactoe::moves::Move;
+
+pub const DEFAULT_TIME_LIMIT: Duration = Duration::from_secs(1);
+// Leeway on top of the time limit for the answer to make it through the pipe
+const GRACE_TIME: Duration = Duration::from_millis(200);
+
+// A program speaking the protocol in crate::protocol, run as a subprocess
+pub struct Bot {
+    name: String,
+    process: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    time_limit: Duration,
+    // The settings the bot last heard about
+    settings: Option<GameSettings>,
+    // When the move it is working on was asked for
+    asked: Option<Instant>,
+    // Answers still to come for positions that were undone
+    stale: usize,
+}
+
+impl Bot {
+    // `command` is the program followed by its arguments
+    pub fn launch(command: &str) -> Result<Self> {
+        let mut parts = command.split_whitespace();
+        let program = parts.next().ok_or_else(|| anyhow!("No bot to launch."))?;
+        let mut process = Process::new(program)
+            .args(parts)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            // It would draw over the board otherwise
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|error| anyhow!("Couldn't launch '{}': {}", program, error))?;
+
+        let stdin = process.stdin.take().expect("stdin is piped");
+        let lines = spawn_line_reader(process.stdout.take().expect("stdout is piped"));
+
+        let mut bot = Self {
+            name: program.to_string(),
+            process,
+            stdin,
+            lines,
+            time_limit: DEFAULT_TIME_LIMIT,
+            settings: None,
+            asked: None,
+            stale: 0,
+        };
+        bot.handshake()?;
+        Ok(bot)
+    }
+
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = time_limit;
+        self
+    }
+
+    fn handshake(&mut self) -> Result<()> {
+        self.send(&Command::Hello).map_err(|_| anyhow!("{} quit right away.", self.name))?;
+        let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+        loop {
+            match self.lines.recv_deadline(deadline).map(|line| Reply::parse(&line)) {
+                Ok(Some(Ok(Reply::Name(name)))) => self.name = name,
+                Ok(Some(Ok(Reply::Ready))) => return Ok(()),
+                Ok(_) => {}
+                Err(RecvTimeoutError::Timeout) => return Err(anyhow!("{} didn't answer in time.", self.name)),
+                Err(RecvTimeoutError::Disconnected) => return Err(anyhow!("{} quit right away.", self.name)),
+            }
+        }
+    }
+
+    fn send(&mut self, command: &Command) -> Result<()> {
+        writeln!(self.stdin, "{}", command)?;
+        Ok(())
+    }
+
+    fn ask(&mut self, game: &TicTacToe) -> Result<()> {
+        let settings = GameSettings::of(game);
+        if self.settings != Some(settings) {
+            self.send(&Command::NewGame(settings))?;
+            self.settings = Some(settings);
+        }
+        self.send(&Command::Position(game.history().to_vec()))?;
+        self.send(&Command::Go(self.time_limit))?;
+        self.asked = Some(Instant::now());
+        Ok(())
+    }
+}
+
+impl Controller for Bot {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn poll_move(&mut self, game: &TicTacToe, _picked: Option<&Move>) -> Result<Option<Move>> {
+        let Some(asked) = self.asked else {
+            self.ask(game)?;
+            return Ok(None);
+        };
+
+        loop {
+            // Lines that aren't replies are skipped
+            match self.lines.try_recv().map(|line| Reply::parse(&line)) {
+                Ok(Some(Ok(Reply::BestMove(_)))) if self.stale > 0 => self.stale -= 1,
+                Ok(Some(Ok(Reply::BestMove(mv)))) => {
+                    self.asked = None;
+                    return mv.map(Some).ok_or_else(|| anyhow!("{} found no move.", self.name));
+                }
+                Ok(Some(Err(error))) => return Err(error),
+                Ok(_) => {}
+                Err(TryRecvError::Empty) if asked.elapsed() > self.time_limit + GRACE_TIME => {
+                    return Err(anyhow!("{} ran out of time.", self.name));
+                }
+                Err(TryRecvError::Empty) => return Ok(None),
+                Err(TryRecvError::Disconnected) => return Err(anyhow!("{} quit.", self.name)),
+            }
+        }
+    }
+
+    fn undone(&mut self, _game: &TicTacToe) {
+        if self.asked.take().is_some() {
+            self.stale += 1;
+            // A bot that can't be told is dealt with on the next poll
+            let _ = self.send(&Command::Stop);
+        }
+    }
+}
+
+impl Drop for Bot {
+    fn drop(&mut self) {
+        let _ = self.send(&Command::Quit);
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
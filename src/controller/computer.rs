@@ -0,0 +1,64 @@
+use anyhow::Result;
+
+use crate::ai::Ai;
+use crate::controller::Controller;
+use crate::engine::jobs::Job;
+use crate::tictactoe::game::TicTacToe;
+use crate::tictactoe::moves::Move;
+
+// The Ai back from its job, with the move it chose
+type Thought = (Box<dyn Ai>, Option<Move>);
+
+// Lets an Ai think on a background job so the screen keeps updating. The Ai is handed to the
+// job and comes back with its move.
+pub struct Computer {
+    name: String,
+    ai: Option<Box<dyn Ai>>,
+    job: Option<Job<Thought>>,
+}
+
+impl Computer {
+    pub fn new(ai: Box<dyn Ai>) -> Self {
+        Self {
+            name: ai.name(),
+            ai: Some(ai),
+            job: None,
+        }
+    }
+}
+
+impl Controller for Computer {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn poll_move(&mut self, game: &TicTacToe, _picked: Option<&Move>) -> Result<Option<Move>> {
+        if let Some(job) = &self.job {
+            let Some((ai, mv)) = job.poll()? else {
+                return Ok(None);
+            };
+            let cancelled = job.token().is_cancelled();
+            self.ai = Some(ai);
+            self.job = None;
+            // A cancelled job thought about a position that was undone, start over
+            if !cancelled {
+                return Ok(mv);
+            }
+        }
+
+        if let Some(mut ai) = self.ai.take() {
+            let position = game.clone();
+            self.job = Some(Job::spawn(move |token| {
+                let mv = ai.choose_move_until(&position, &|| token.is_cancelled());
+                (ai, mv)
+            }));
+        }
+        Ok(None)
+    }
+
+    fn undone(&mut self, _game: &TicTacToe) {
+        if let Some(job) = &self.job {
+            job.cancel();
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use anyhow::Result;
+
+use crate::controller::Controller;
+use crate::tictactoe::game::TicTacToe;
+use crate::tictactoe::moves::Move;
+
+// Someone at this terminal
+#[derive(Default)]
+pub struct Keyboard;
+
+impl Controller for Keyboard {
+    fn name(&self) -> String {
+        "Keyboard".to_string()
+    }
+
+    fn is_local(&self) -> bool {
+        true
+    }
+
+    fn poll_move(&mut self, _game: &TicTacToe, picked: Option<&Move>) -> Result<Option<Move>> {
+        Ok(picked.cloned())
+    }
+}
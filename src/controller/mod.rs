@@ -0,0 +1,63 @@
+use std::io::{BufRead, BufReader, Read};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+use crossbeam_channel::Receiver;
+
+use crate::tictactoe::game::TicTacToe;
+use crate::tictactoe::moves::Move;
+
+pub mod bot;
+pub mod computer;
+pub mod keyboard;
+pub mod network;
+pub mod scripted;
+
+// How long a bot or a network peer gets to introduce itself
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Decides the moves of one side: someone at the keyboard, the computer, a bot running as a
+// subprocess, a peer over the network or a script
+pub trait Controller: Send {
+    fn name(&self) -> String;
+
+    // Local sides pick their moves with the cursor
+    fn is_local(&self) -> bool {
+        false
+    }
+
+    // Asked every frame while it is this side's turn in a game still in progress, None until
+    // it has decided. `picked` is the move picked at the keyboard this frame, if any.
+    fn poll_move(&mut self, game: &TicTacToe, picked: Option<&Move>) -> Result<Option<Move>>;
+
+    // Hears about every move once it is played, whichever side played it
+    fn move_played(&mut self, _game: &TicTacToe, _mv: &Move) -> Result<()> {
+        Ok(())
+    }
+
+    // Whether moves can be taken back while this controller plays a side
+    fn allows_undo(&self) -> bool {
+        true
+    }
+
+    // Moves were taken back, anything worked out for the old position is stale
+    fn undone(&mut self, _game: &TicTacToe) {}
+}
+
+// Reads `input` line by line on a thread of its own. The channel disconnects once the input
+// ends, e.g. when a bot exits or a peer goes away.
+fn spawn_line_reader(input: impl Read + Send + 'static) -> Receiver<String> {
+    let (tx, lines) = crossbeam_channel::unbounded();
+    thread::spawn(move || {
+        for line in BufReader::new(input).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    lines
+}
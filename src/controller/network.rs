@@ -0,0 +1,91 @@
+use std::io::Write;
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+
+use anyhow::{anyhow, Result};
+use crossbeam_channel::{Receiver, RecvTimeoutError, TryRecvError};
+
+use crate::controller::{spawn_line_reader, Controller, HANDSHAKE_TIMEOUT};
+use crate::protocol::{Command, GameSettings};
+use crate::tictactoe::game::TicTacToe;
+use crate::tictactoe::moves::Move;
+
+// The other side of a game played over TCP. Both ends first send the game they started as a
+// `newgame` line from crate::protocol and refuse to play a different one, then send their own
+// moves, one per line, like "move b2 x".
+pub struct NetworkPeer {
+    address: String,
+    stream: TcpStream,
+    lines: Receiver<String>,
+    // The peer's last move, which it doesn't need to hear about
+    received: Option<Move>,
+}
+
+impl NetworkPeer {
+    // Waits for a peer to connect
+    pub fn host(address: impl ToSocketAddrs, game: &TicTacToe) -> Result<Self> {
+        let (stream, _) = TcpListener::bind(address)?.accept()?;
+        NetworkPeer::from_stream(stream, game)
+    }
+
+    pub fn connect(address: impl ToSocketAddrs, game: &TicTacToe) -> Result<Self> {
+        NetworkPeer::from_stream(TcpStream::connect(address)?, game)
+    }
+
+    fn from_stream(stream: TcpStream, game: &TicTacToe) -> Result<Self> {
+        let address = stream.peer_addr()?.to_string();
+        let lines = spawn_line_reader(stream.try_clone()?);
+        let mut peer = Self { address, stream, lines, received: None };
+        peer.handshake(GameSettings::of(game))?;
+        Ok(peer)
+    }
+
+    fn handshake(&mut self, settings: GameSettings) -> Result<()> {
+        writeln!(self.stream, "{}", Command::NewGame(settings))?;
+        let line = match self.lines.recv_timeout(HANDSHAKE_TIMEOUT) {
+            Ok(line) => line,
+            Err(RecvTimeoutError::Timeout) => return Err(anyhow!("{} didn't say which game it started.", self.address)),
+            Err(RecvTimeoutError::Disconnected) => return Err(anyhow!("{} disconnected.", self.address)),
+        };
+
+        match line.parse() {
+            Ok(Command::NewGame(theirs)) if theirs == settings => Ok(()),
+            Ok(Command::NewGame(theirs)) => {
+                Err(anyhow!("{} started a different game ({}) from this one ({}).", self.address, theirs, settings))
+            }
+            _ => Err(anyhow!("{} sent '{}' instead of its game.", self.address, line)),
+        }
+    }
+}
+
+impl Controller for NetworkPeer {
+    fn name(&self) -> String {
+        format!("Peer at {}", self.address)
+    }
+
+    fn poll_move(&mut self, game: &TicTacToe, _picked: Option<&Move>) -> Result<Option<Move>> {
+        let line = match self.lines.try_recv() {
+            Ok(line) => line,
+            Err(TryRecvError::Empty) => return Ok(None),
+            Err(TryRecvError::Disconnected) => return Err(anyhow!("{} disconnected.", self.address)),
+        };
+
+        let mv = line.strip_prefix("move ")
+            .and_then(|notation| Move::from_notation(notation, game))
+            .ok_or_else(|| anyhow!("{} sent '{}', which is not a move.", self.address, line))?;
+        self.received = Some(mv.clone());
+        Ok(Some(mv))
+    }
+
+    fn move_played(&mut self, _game: &TicTacToe, mv: &Move) -> Result<()> {
+        if self.received.take().as_ref() == Some(mv) {
+            return Ok(());
+        }
+        writeln!(self.stream, "move {}", mv.to_notation())?;
+        Ok(())
+    }
+
+    // The peer's board would no longer match
+    fn allows_undo(&self) -> bool {
+        false
+    }
+}
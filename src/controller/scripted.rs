@@ -0,0 +1,80 @@
+use std::collections::VecDeque;
+use std::fs::read_to_string;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+
+use crate::controller::Controller;
+use crate::tictactoe::game::TicTacToe;
+use crate::tictactoe::moves::Move;
+use crate::tictactoe::player::Player;
+
+// Plays a fixed list of moves, e.g. one side of a recorded game
+pub struct Scripted {
+    moves: VecDeque<Move>,
+    // Pause before each move, so a replay can be followed
+    delay: Duration,
+    // When the current move was first asked for
+    asked: Option<Instant>,
+}
+
+impl Scripted {
+    pub fn new(moves: impl IntoIterator<Item = Move>) -> Self {
+        Self {
+            moves: moves.into_iter().collect(),
+            delay: Duration::ZERO,
+            asked: None,
+        }
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    // Both sides of a game recorded one move per line, like `b2` or `b2 x`, played from `game`.
+    // Blank lines and anything after a # are skipped.
+    pub fn replay<P: AsRef<Path>>(path: P, game: &TicTacToe) -> Result<[Scripted; 2]> {
+        let content = read_to_string(path)?;
+        let mut position = game.clone();
+        let mut sides = [vec![], vec![]];
+
+        for (number, line) in content.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mv = Move::from_notation(line, &position)
+                .ok_or_else(|| anyhow!("Line {}: '{}' is not a move like b2 or b2 x.", number + 1, line))?;
+            let side = if *position.turn() == Player::ONE { 0 } else { 1 };
+            position.play_move(&mv).map_err(|error| anyhow!("Line {}: {}", number + 1, error))?;
+            sides[side].push(mv);
+        }
+
+        Ok(sides.map(Scripted::new))
+    }
+}
+
+impl Controller for Scripted {
+    fn name(&self) -> String {
+        "Script".to_string()
+    }
+
+    fn poll_move(&mut self, _game: &TicTacToe, _picked: Option<&Move>) -> Result<Option<Move>> {
+        if self.moves.is_empty() {
+            return Err(anyhow!("No moves left to replay."));
+        }
+        let asked = *self.asked.get_or_insert_with(Instant::now);
+        if asked.elapsed() < self.delay {
+            return Ok(None);
+        }
+        self.asked = None;
+        Ok(self.moves.pop_front())
+    }
+
+    // The script can't know which of its moves to play again
+    fn allows_undo(&self) -> bool {
+        false
+    }
+}
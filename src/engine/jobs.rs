@@ -0,0 +1,75 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use anyhow::{anyhow, Result};
+use crossbeam_channel::{Receiver, TryRecvError};
+
+// Handed to a job so it can notice it is no longer wanted and stop early
+#[derive(Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    // Only the holder of this token is left: the job has finished and its handle was dropped
+    pub(crate) fn is_abandoned(&self) -> bool {
+        Arc::strong_count(&self.cancelled) == 1
+    }
+}
+
+// Work running on its own thread, polled by logic functions until its result arrives.
+// Dropping the job cancels it.
+pub struct Job<T> {
+    rx: Receiver<T>,
+    token: CancelToken,
+}
+
+impl<T: Send + 'static> Job<T> {
+    pub fn spawn<F>(work: F) -> Self
+    where
+        F: FnOnce(CancelToken) -> T + Send + 'static,
+    {
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        let token = CancelToken::default();
+        let job_token = token.clone();
+
+        thread::spawn(move || {
+            // Nobody is listening any more if the job was dropped
+            let _ = tx.send(work(job_token));
+        });
+
+        Self { rx, token }
+    }
+
+    // The result once the job is done, None while it is still running
+    pub fn poll(&self) -> Result<Option<T>> {
+        match self.rx.try_recv() {
+            Ok(result) => Ok(Some(result)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(anyhow!("Background job stopped without a result.")),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    pub fn token(&self) -> &CancelToken {
+        &self.token
+    }
+}
+
+impl<T> Drop for Job<T> {
+    fn drop(&mut self) {
+        self.token.cancel();
+    }
+}
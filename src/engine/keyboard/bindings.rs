@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl From<KeyCode> for KeyBinding {
+    fn from(code: KeyCode) -> Self {
+        Self { code, modifiers: KeyModifiers::NONE }
+    }
+}
+
+impl KeyBinding {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        normalize(self.code, self.modifiers) == normalize(event.code, event.modifiers)
+    }
+}
+
+// Terminals disagree on whether Shift is reported alongside the character it produced,
+// so letters carry it as a modifier and other characters not at all
+fn normalize(code: KeyCode, modifiers: KeyModifiers) -> (KeyCode, KeyModifiers) {
+    match code {
+        KeyCode::Char(char) if char.is_uppercase() => {
+            (KeyCode::Char(char.to_ascii_lowercase()), modifiers | KeyModifiers::SHIFT)
+        }
+        KeyCode::Char(char) if !char.is_alphabetic() => (code, modifiers - KeyModifiers::SHIFT),
+        _ => (code, modifiers),
+    }
+}
+
+// Maps named actions (e.g. "move_left") to the keys that trigger them
+#[derive(Default, Clone)]
+pub struct KeyBindings {
+    actions: HashMap<String, Vec<KeyBinding>>,
+}
+
+impl KeyBindings {
+    pub fn preset(name: &str) -> Result<Self> {
+        let bindings = KeyBindings::default()
+            .bind("quit", KeyCode::Esc)
+            .bind("quit", KeyCode::Char('q'))
+            .bind("place", KeyCode::Enter)
+            .bind("place", KeyCode::Char(' '))
+            .bind("move_left", KeyCode::Left)
+            .bind("move_right", KeyCode::Right)
+            .bind("move_up", KeyCode::Up)
+            .bind("move_down", KeyCode::Down);
+
+        match name {
+            "arrows" => Ok(bindings),
+            "wasd" => Ok(bindings
+                .bind("move_left", KeyCode::Char('a'))
+                .bind("move_right", KeyCode::Char('d'))
+                .bind("move_up", KeyCode::Char('w'))
+                .bind("move_down", KeyCode::Char('s'))),
+            "vim" => Ok(bindings
+                .bind("move_left", KeyCode::Char('h'))
+                .bind("move_right", KeyCode::Char('l'))
+                .bind("move_up", KeyCode::Char('k'))
+                .bind("move_down", KeyCode::Char('j'))),
+            _ => Err(anyhow!("Unknown key binding preset '{}'.", name)),
+        }
+    }
+
+    // One binding per line: `action = key, Ctrl+key`. A `preset = name` line starts from that preset
+    // and later lines for an action replace the preset's keys for it.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let content = read_to_string(path)?;
+        KeyBindings::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut bindings = KeyBindings::default();
+
+        for (number, line) in content.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (action, keys) = line.split_once('=')
+                .ok_or_else(|| anyhow!("Line {}: expected 'action = key, key'.", number + 1))?;
+            let action = action.trim();
+
+            if action == "preset" {
+                let preset = KeyBindings::preset(keys.trim())?;
+                bindings.actions.extend(preset.actions);
+                continue;
+            }
+
+            let keys = keys.split(',')
+                .map(|key| parse_key(key.trim()).map_err(|error| anyhow!("Line {}: {}", number + 1, error)))
+                .collect::<Result<Vec<KeyBinding>>>()?;
+            bindings.actions.insert(action.to_string(), keys);
+        }
+
+        Ok(bindings)
+    }
+
+    pub fn bind<K: Into<KeyBinding>>(mut self, action: &str, key: K) -> Self {
+        self.insert(action, key);
+        self
+    }
+
+    pub fn insert<K: Into<KeyBinding>>(&mut self, action: &str, key: K) {
+        let key = key.into();
+        let keys = self.actions.entry(action.to_string()).or_default();
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    pub fn keys(&self, action: &str) -> &[KeyBinding] {
+        self.actions.get(action).map_or(&[], |keys| keys.as_slice())
+    }
+
+    pub fn is_bound(&self, action: &str, event: &KeyEvent) -> bool {
+        self.keys(action).iter().any(|key| key.matches(event))
+    }
+}
+
+fn parse_key(key: &str) -> Result<KeyBinding> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut parts: Vec<&str> = key.split('+').collect();
+
+    // A trailing empty part means the key itself is '+', e.g. "Ctrl++"
+    let code = match parts.pop() {
+        Some("") if parts.last() == Some(&"") => {
+            parts.pop();
+            "+"
+        }
+        Some(code) => code,
+        None => key,
+    };
+
+    for modifier in parts {
+        modifiers |= match modifier.to_lowercase().as_str() {
+            "ctrl" | "control" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return Err(anyhow!("Unknown modifier '{}'.", modifier)),
+        };
+    }
+
+    Ok(KeyBinding::new(parse_key_code(code)?, modifiers))
+}
+
+fn parse_key_code(key: &str) -> Result<KeyCode> {
+    let mut chars = key.chars();
+    if let (Some(char), None) = (chars.next(), chars.next()) {
+        return Ok(KeyCode::Char(char));
+    }
+
+    let lowercase = key.to_lowercase();
+    if let Some(number) = lowercase.strip_prefix('f').and_then(|number| number.parse().ok()) {
+        return Ok(KeyCode::F(number));
+    }
+
+    match lowercase.as_str() {
+        "space" => Ok(KeyCode::Char(' ')),
+        "enter" => Ok(KeyCode::Enter),
+        "esc" | "escape" => Ok(KeyCode::Esc),
+        "tab" => Ok(KeyCode::Tab),
+        "backspace" => Ok(KeyCode::Backspace),
+        "delete" => Ok(KeyCode::Delete),
+        "left" => Ok(KeyCode::Left),
+        "right" => Ok(KeyCode::Right),
+        "up" => Ok(KeyCode::Up),
+        "down" => Ok(KeyCode::Down),
+        "home" => Ok(KeyCode::Home),
+        "end" => Ok(KeyCode::End),
+        "pageup" => Ok(KeyCode::PageUp),
+        "pagedown" => Ok(KeyCode::PageDown),
+        _ => Err(anyhow!("Unknown key '{}'.", key)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_bind_actions_to_keys_with_modifiers() {
+        let bindings = KeyBindings::parse("undo = u, Ctrl+z  # comment\nzoom = Ctrl++\nquit = Esc").unwrap();
+        assert_eq!(bindings.keys("undo"), [
+            KeyBinding::from(KeyCode::Char('u')),
+            KeyBinding::new(KeyCode::Char('z'), KeyModifiers::CONTROL),
+        ]);
+        assert_eq!(bindings.keys("zoom"), [KeyBinding::new(KeyCode::Char('+'), KeyModifiers::CONTROL)]);
+        assert_eq!(bindings.keys("quit"), [KeyBinding::from(KeyCode::Esc)]);
+    }
+
+    #[test]
+    fn later_lines_replace_the_preset_keys() {
+        let bindings = KeyBindings::parse("preset = vim\nmove_left = a").unwrap();
+        assert_eq!(bindings.keys("move_left"), [KeyBinding::from(KeyCode::Char('a'))]);
+        assert!(bindings.keys("move_down").contains(&KeyBinding::from(KeyCode::Char('j'))));
+    }
+
+    #[test]
+    fn errors_name_the_line() {
+        let error = KeyBindings::parse("quit = q\nundo = Hyper+u").err().unwrap();
+        assert_eq!(error.to_string(), "Line 2: Unknown modifier 'Hyper'.");
+        assert!(KeyBindings::parse("quit q").is_err());
+    }
+
+    #[test]
+    fn shift_only_matters_for_letters() {
+        let shifted = |char| KeyEvent::new(KeyCode::Char(char), KeyModifiers::SHIFT);
+        assert!(KeyBinding::from(KeyCode::Char('?')).matches(&shifted('?')));
+        assert!(KeyBinding::from(KeyCode::Char('U')).matches(&shifted('U')));
+        assert!(!KeyBinding::from(KeyCode::Char('u')).matches(&shifted('U')));
+    }
+}
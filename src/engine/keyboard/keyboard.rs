@@ -2,16 +2,16 @@ use std::time::Duration;
 
 use anyhow::Result;
 use crossterm::event;
-use crossterm::event::{Event, KeyCode};
+use crossterm::event::{Event, KeyEvent};
 
 pub struct Keyboard {}
 
 impl Keyboard {
-    pub fn poll_keys() -> Result<Vec<KeyCode>> {
+    pub fn poll_keys() -> Result<Vec<KeyEvent>> {
         let mut keys = Vec::new();
         while event::poll(Duration::default())? {
             if let Event::Key(key_event) = event::read()? {
-                keys.push(key_event.code);
+                keys.push(key_event);
             }
         }
 
@@ -1 +1,4 @@
-pub mod keyboard;
\ No newline at end of file
+#[allow(clippy::module_inception)]
+pub mod keyboard;
+pub mod bindings;
+pub mod state;
\ No newline at end of file
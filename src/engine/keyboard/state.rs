@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum KeyRepeat {
+    // Repeats are whatever the terminal sends while a key is held
+    #[default]
+    Terminal,
+    Disabled,
+    // Repeats are generated by the engine once a key has been held for `delay`. Without
+    // release events there is no telling how long a key is held, so none are generated.
+    Custom { delay: Duration, interval: Duration },
+}
+
+struct HeldKey {
+    modifiers: KeyModifiers,
+    since: Instant,
+    last_event: Instant,
+    last_repeat: Instant,
+}
+
+// Per-frame keyboard state. Terminals without enhanced keyboard support never report releases
+// and send their repeats as presses, so there every event counts as a press and keys are
+// released after `release_timeout`.
+pub struct KeyState {
+    enhanced: bool,
+    repeat: KeyRepeat,
+    release_timeout: Duration,
+    pressed: Vec<KeyEvent>,
+    repeated: Vec<KeyEvent>,
+    released: Vec<KeyEvent>,
+    held: HashMap<KeyCode, HeldKey>,
+    modifiers: KeyModifiers,
+}
+
+impl Default for KeyState {
+    fn default() -> Self {
+        Self {
+            enhanced: false,
+            repeat: KeyRepeat::default(),
+            release_timeout: Duration::from_millis(500),
+            pressed: vec![],
+            repeated: vec![],
+            released: vec![],
+            held: HashMap::default(),
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+}
+
+impl KeyState {
+    pub fn set_enhanced(&mut self, enhanced: bool) {
+        self.enhanced = enhanced;
+    }
+
+    pub fn set_repeat(&mut self, repeat: KeyRepeat) {
+        self.repeat = repeat;
+    }
+
+    pub fn update(&mut self, events: Vec<KeyEvent>, now: Instant) {
+        self.pressed.clear();
+        self.repeated.clear();
+        self.released.clear();
+
+        for event in events {
+            // Some platforms report event kinds without being asked to
+            if event.kind != KeyEventKind::Press {
+                self.enhanced = true;
+            }
+            self.modifiers = event.modifiers;
+
+            match event.kind {
+                KeyEventKind::Press => {
+                    self.pressed.push(event);
+                    self.held.insert(event.code, HeldKey {
+                        modifiers: event.modifiers,
+                        since: now,
+                        last_event: now,
+                        last_repeat: now,
+                    });
+                }
+                KeyEventKind::Repeat => {
+                    if let Some(held) = self.held.get_mut(&event.code) {
+                        held.last_event = now;
+                    }
+                    if self.repeat == KeyRepeat::Terminal {
+                        self.repeated.push(event);
+                    }
+                }
+                KeyEventKind::Release => {
+                    self.held.remove(&event.code);
+                    self.released.push(event);
+                }
+            }
+        }
+
+        if !self.enhanced {
+            let timeout = self.release_timeout;
+            let expired: Vec<KeyCode> = self.held.iter()
+                .filter(|(_, held)| now.duration_since(held.last_event) >= timeout)
+                .map(|(code, _)| *code)
+                .collect();
+            for code in expired {
+                if let Some(held) = self.held.remove(&code) {
+                    self.released.push(KeyEvent::new_with_kind(code, held.modifiers, KeyEventKind::Release));
+                }
+            }
+        }
+
+        // Only releases tell a held key from one that was tapped
+        match self.repeat {
+            KeyRepeat::Custom { delay, interval } if self.enhanced => {
+                for (code, held) in self.held.iter_mut() {
+                    if now.duration_since(held.since) >= delay && now.duration_since(held.last_repeat) >= interval {
+                        held.last_repeat = now;
+                        self.repeated.push(KeyEvent::new_with_kind(*code, held.modifiers, KeyEventKind::Repeat));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn pressed(&self) -> &[KeyEvent] {
+        &self.pressed
+    }
+
+    pub fn released(&self) -> &[KeyEvent] {
+        &self.released
+    }
+
+    pub fn held(&self) -> impl Iterator<Item = KeyEvent> + '_ {
+        self.held.iter().map(|(code, held)| KeyEvent::new(*code, held.modifiers))
+    }
+
+    // Presses and repeats, which is what menus and cursor movement usually want
+    pub fn triggered(&self) -> impl Iterator<Item = &KeyEvent> {
+        self.pressed.iter().chain(self.repeated.iter())
+    }
+
+    pub fn is_just_pressed(&self, code: KeyCode) -> bool {
+        self.pressed.iter().any(|event| event.code == code)
+    }
+
+    pub fn is_repeated(&self, code: KeyCode) -> bool {
+        self.repeated.iter().any(|event| event.code == code)
+    }
+
+    pub fn is_held(&self, code: KeyCode) -> bool {
+        self.held.contains_key(&code)
+    }
+
+    pub fn is_released(&self, code: KeyCode) -> bool {
+        self.released.iter().any(|event| event.code == code)
+    }
+
+    // Modifiers of the most recent key event
+    pub fn modifiers(&self) -> KeyModifiers {
+        self.modifiers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CUSTOM: KeyRepeat = KeyRepeat::Custom { delay: Duration::from_millis(200), interval: Duration::from_millis(50) };
+
+    fn key(kind: KeyEventKind) -> KeyEvent {
+        KeyEvent::new_with_kind(KeyCode::Right, KeyModifiers::NONE, kind)
+    }
+
+    fn state(repeat: KeyRepeat, enhanced: bool) -> KeyState {
+        let mut state = KeyState::default();
+        state.set_repeat(repeat);
+        state.set_enhanced(enhanced);
+        state
+    }
+
+    // Presses and repeats of every frame, one frame every 10ms
+    fn run(state: &mut KeyState, frames: Vec<Vec<KeyEvent>>) -> (usize, usize) {
+        let start = Instant::now();
+        let (mut pressed, mut repeated) = (0, 0);
+        for (frame, events) in frames.into_iter().enumerate() {
+            state.update(events, start + Duration::from_millis(10 * frame as u64));
+            pressed += state.pressed().len();
+            repeated += state.repeated.len();
+        }
+        (pressed, repeated)
+    }
+
+    fn tap(frames: usize) -> Vec<Vec<KeyEvent>> {
+        let mut events = vec![vec![key(KeyEventKind::Press)]];
+        events.resize(frames, vec![]);
+        events
+    }
+
+    // What an enhanced terminal sends for a held key: repeats every 30ms
+    fn held_with_repeats(frames: usize) -> Vec<Vec<KeyEvent>> {
+        let mut events: Vec<Vec<KeyEvent>> = (0..frames)
+            .map(|frame| if frame % 3 == 0 { vec![key(KeyEventKind::Repeat)] } else { vec![] })
+            .collect();
+        events[0] = vec![key(KeyEventKind::Press)];
+        events
+    }
+
+    #[test]
+    fn taps_never_repeat_without_releases() {
+        assert_eq!(run(&mut state(CUSTOM, false), tap(60)), (1, 0));
+    }
+
+    #[test]
+    fn quick_taps_are_all_presses_without_releases() {
+        let mut frames = tap(20);
+        frames[10].push(key(KeyEventKind::Press));
+        for repeat in [KeyRepeat::Terminal, KeyRepeat::Disabled, CUSTOM] {
+            assert_eq!(run(&mut state(repeat, false), frames.clone()), (2, 0));
+        }
+    }
+
+    #[test]
+    fn only_terminal_repeats_are_passed_on() {
+        assert_eq!(run(&mut state(KeyRepeat::Terminal, true), held_with_repeats(10)), (1, 3));
+        assert_eq!(run(&mut state(KeyRepeat::Disabled, true), held_with_repeats(10)), (1, 0));
+        assert_eq!(run(&mut state(CUSTOM, true), held_with_repeats(10)), (1, 0));
+    }
+
+    #[test]
+    fn keys_are_released_once_the_terminal_goes_quiet() {
+        let mut state = state(KeyRepeat::Terminal, false);
+        run(&mut state, tap(49));
+        assert!(state.is_held(KeyCode::Right));
+        state.update(vec![], Instant::now() + Duration::from_millis(500));
+        assert!(state.is_released(KeyCode::Right));
+    }
+
+    #[test]
+    fn custom_repeats_follow_releases() {
+        let mut frames = tap(40);
+        frames[32].push(key(KeyEventKind::Release));
+        // Repeats at 200, 250 and 300ms, released at 320ms
+        assert_eq!(run(&mut state(CUSTOM, true), frames), (1, 3));
+    }
+}
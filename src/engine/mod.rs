@@ -1,35 +1,53 @@
 use anyhow::Result;
 use std::collections::HashMap;
 use std::mem::take;
-use std::thread;
-use std::time::Duration;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::time::{Duration, Instant};
 use crossterm::event::KeyCode;
 use crate::engine::drawable::Drawable;
 use crate::engine::framerate::FrameRate;
+use crate::engine::jobs::{CancelToken, Job};
+use crate::engine::keyboard::bindings::KeyBindings;
 use crate::engine::keyboard::keyboard::Keyboard;
+use crate::engine::keyboard::state::{KeyRepeat, KeyState};
 use crate::engine::rendering::frame;
-use crate::engine::rendering::renderer::Renderer;
+use crate::engine::rendering::layers::RenderLayers;
+use crate::engine::rendering::renderer::{FramePolicy, Renderer};
 use crate::engine::rendering::terminal::Terminal;
 use crate::engine::sprite::Sprite;
 use crate::engine::timing::delta::Delta;
 use crate::engine::window::Window;
 
+pub mod jobs;
+pub mod keyboard;
+pub mod rendering;
 pub mod sprite;
 pub mod window;
 
 mod framerate;
-mod keyboard;
 mod drawable;
-mod rendering;
 mod timing;
 
+struct SpriteEntry {
+    order: u64,
+    sprite: Sprite,
+}
+
 pub struct Engine<State> {
     fps: u64,
     window: Window,
-    sprites: HashMap<String, Sprite>,
+    sprites: HashMap<String, SpriteEntry>,
+    next_sprite_order: u64,
+    render_layers: RenderLayers,
+    frame_policy: FramePolicy,
+    dropped_frames: u64,
     pub delta: Duration,
-    pressed_keys: Vec<KeyCode>,
+    key_state: KeyState,
+    key_bindings: KeyBindings,
     logic_fns: Vec<fn(&mut Engine<State>, &mut State)>,
+    // Tokens of the background jobs that may still be running
+    jobs: Vec<CancelToken>,
     stop: bool,
 }
 
@@ -39,9 +57,15 @@ impl<State> Default for Engine<State> {
             fps: 60,
             window: Window::new(100, 100),
             sprites: HashMap::default(),
+            next_sprite_order: 0,
+            render_layers: RenderLayers::default(),
+            frame_policy: FramePolicy::default(),
+            dropped_frames: 0,
             logic_fns: vec![],
             delta: Duration::from_millis(0),
-            pressed_keys: vec![],
+            key_state: KeyState::default(),
+            key_bindings: KeyBindings::default(),
+            jobs: vec![],
             stop: false
         }
     }
@@ -54,9 +78,15 @@ impl<State> Engine<State> {
             window,
             fps: 60,
             sprites: HashMap::default(),
+            next_sprite_order: 0,
+            render_layers: RenderLayers::default(),
+            frame_policy: FramePolicy::default(),
+            dropped_frames: 0,
             logic_fns: vec![],
             delta: Duration::from_millis(0),
-            pressed_keys: vec![],
+            key_state: KeyState::default(),
+            key_bindings: KeyBindings::default(),
+            jobs: vec![],
             stop: false
         }
     }
@@ -66,13 +96,40 @@ impl<State> Engine<State> {
         self
     }
 
+    pub fn with_frame_policy(mut self, frame_policy: FramePolicy) -> Self {
+        self.frame_policy = frame_policy;
+        self
+    }
+
+    pub fn with_key_repeat(mut self, repeat: KeyRepeat) -> Self {
+        self.key_state.set_repeat(repeat);
+        self
+    }
+
+    pub fn with_key_bindings(mut self, key_bindings: KeyBindings) -> Self {
+        self.key_bindings = key_bindings;
+        self
+    }
+
     pub fn with_sprite(mut self, label: &str, sprite: Sprite) -> Self {
-        self.sprites.insert(label.to_string(), sprite);
+        self.insert_sprite(label, sprite);
+        self
+    }
+
+    pub fn with_render_layer(mut self, name: &str, z: i32) -> Self {
+        self.render_layers.insert(name, z);
         self
     }
 
+    // Replacing a sprite keeps its original draw order so it doesn't jump above its peers
     pub fn insert_sprite(&mut self, label: &str, sprite: Sprite) {
-        self.sprites.insert(label.into(), sprite);
+        if let Some(entry) = self.sprites.get_mut(label) {
+            entry.sprite = sprite;
+        } else {
+            let order = self.next_sprite_order;
+            self.next_sprite_order += 1;
+            self.sprites.insert(label.into(), SpriteEntry { order, sprite });
+        }
     }
 
     pub fn remove_sprite(&mut self, label: &str) {
@@ -80,7 +137,24 @@ impl<State> Engine<State> {
     }
 
     pub fn get_sprite(&self, label: &str) -> Option<&Sprite> {
-        self.sprites.get(label)
+        self.sprites.get(label).map(|entry| &entry.sprite)
+    }
+
+    pub fn show_layer(&mut self, name: &str) {
+        self.render_layers.set_visible(name, true);
+    }
+
+    pub fn hide_layer(&mut self, name: &str) {
+        self.render_layers.set_visible(name, false);
+    }
+
+    pub fn toggle_layer(&mut self, name: &str) {
+        let visible = self.is_layer_visible(name);
+        self.render_layers.set_visible(name, !visible);
+    }
+
+    pub fn is_layer_visible(&self, name: &str) -> bool {
+        self.render_layers.is_visible(Some(name))
     }
 
     pub fn with_logic(mut self, logic_fn: fn(&mut Engine<State>, &mut State)) -> Self {
@@ -91,14 +165,39 @@ impl<State> Engine<State> {
     pub fn run(&mut self, mut state: State) -> Result<()> {
         self.stop = false;
 
-        let mut delta_holder = Delta::default();
-        let mut renderer = Renderer::new(self.window.width, self.window.height);
-        let mut terminal = Terminal::default();
-
+        let mut renderer = Renderer::new(self.window.width, self.window.height, self.frame_policy);
         let mut logic_fns = take(&mut self.logic_fns);
 
+        let terminal = Terminal::setup()?;
+        self.key_state.set_enhanced(Terminal::is_keyboard_enhanced());
         renderer.run();
-        terminal.setup()?;
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            self.game_loop(&renderer, &mut logic_fns, &mut state)
+        }));
+
+        // Jobs are told to stop but not waited for, one stuck in a long computation mustn't hold up quitting
+        for token in self.jobs.drain(..) {
+            token.cancel();
+        }
+
+        // The render thread is stopped before unwinding any further so it can't draw over the restored terminal
+        let render_result = renderer.stop();
+        let cleanup_result = terminal.cleanup();
+
+        let result = match result {
+            Ok(result) => result,
+            Err(panic) => panic::resume_unwind(panic),
+        };
+
+        // The game loop only notices that rendering stopped, the render thread knows the actual cause
+        render_result?;
+        result?;
+        cleanup_result
+    }
+
+    fn game_loop(&mut self, renderer: &Renderer, logic_fns: &mut [fn(&mut Engine<State>, &mut State)], state: &mut State) -> Result<()> {
+        let mut delta_holder = Delta::default();
 
         while !self.stop {
             // Delta Computation
@@ -109,40 +208,84 @@ impl<State> Engine<State> {
             let mut frame = frame::new_frame(self.window.width, self.window.height);
 
             // Get Keyboard keys
-            self.pressed_keys = Keyboard::poll_keys()?;
+            self.key_state.update(Keyboard::poll_keys()?, Instant::now());
 
             // Logic
             for logic_fn in logic_fns.iter_mut() {
-                logic_fn(self, &mut state);
+                logic_fn(self, state);
             }
 
             // Draw
-            let mut sprites: Vec<&Sprite> = self.sprites.values().collect();
-            sprites.sort_by(|a,b| a.layer.partial_cmp(&b.layer).unwrap());
-            for sprite in sprites.iter() {
-                sprite.draw(&mut frame);
+            let mut entries: Vec<&SpriteEntry> = self.sprites.values()
+                .filter(|entry| self.render_layers.is_visible(entry.sprite.render_layer.as_deref()))
+                .collect();
+            entries.sort_by_key(|entry| {
+                let render_layer = entry.sprite.render_layer.as_deref();
+                (self.render_layers.z(render_layer), entry.sprite.layer, entry.order)
+            });
+            for entry in entries.iter() {
+                entry.sprite.draw(&mut frame);
             }
 
             // Send Frame
-            renderer.send(frame)?;
+            self.dropped_frames += renderer.send(frame)? as u64;
 
             FrameRate::cap(self.fps, &self.delta);
 
         }
 
-        renderer.stop();
-        terminal.cleanup()?;
-
         Ok(())
-
     }
 
+    // Keys pressed this frame, including repeats
     pub fn get_pressed_keys(&self) -> Vec<KeyCode> {
-        self.pressed_keys.clone()
+        self.key_state.triggered().map(|event| event.code).collect()
+    }
+
+    pub fn keys(&self) -> &KeyState {
+        &self.key_state
+    }
+
+    // Pressed this frame, or repeated while held
+    pub fn is_action_pressed(&self, action: &str) -> bool {
+        self.key_state.triggered().any(|event| self.key_bindings.is_bound(action, event))
+    }
+
+    pub fn is_action_just_pressed(&self, action: &str) -> bool {
+        self.key_state.pressed().iter().any(|event| self.key_bindings.is_bound(action, event))
+    }
+
+    pub fn is_action_held(&self, action: &str) -> bool {
+        self.key_state.held().any(|event| self.key_bindings.is_bound(action, &event))
+    }
+
+    pub fn is_action_released(&self, action: &str) -> bool {
+        self.key_state.released().iter().any(|event| self.key_bindings.is_bound(action, event))
+    }
+
+    pub fn key_bindings(&self) -> &KeyBindings {
+        &self.key_bindings
+    }
+
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped_frames
     }
 
     pub fn stop(&mut self) {
         self.stop = true;
     }
+
+    // Runs `work` on its own thread, poll the job from a logic function to get the result.
+    // The job is cancelled when the engine stops.
+    pub fn spawn_job<T, F>(&mut self, work: F) -> Job<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(CancelToken) -> T + Send + 'static,
+    {
+        self.jobs.retain(|token| !token.is_abandoned());
+        let job = Job::spawn(work);
+        self.jobs.push(job.token().clone());
+        job
+    }
 }
 
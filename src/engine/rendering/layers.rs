@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+
+pub struct RenderLayer {
+    pub z: i32,
+    pub visible: bool,
+}
+
+#[derive(Default)]
+pub struct RenderLayers {
+    layers: HashMap<String, RenderLayer>,
+}
+
+impl RenderLayers {
+    pub fn insert(&mut self, name: &str, z: i32) {
+        self.layers.insert(name.to_string(), RenderLayer { z, visible: true });
+    }
+
+    pub fn set_visible(&mut self, name: &str, visible: bool) {
+        if let Some(layer) = self.layers.get_mut(name) {
+            layer.visible = visible;
+        }
+    }
+
+    pub fn is_visible(&self, name: Option<&str>) -> bool {
+        name.and_then(|name| self.layers.get(name))
+            .is_none_or(|layer| layer.visible)
+    }
+
+    // Sprites without a named layer, or with an unknown one, sit at z 0
+    pub fn z(&self, name: Option<&str>) -> i32 {
+        name.and_then(|name| self.layers.get(name))
+            .map_or(0, |layer| layer.z)
+    }
+}
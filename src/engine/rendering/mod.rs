@@ -1,3 +1,4 @@
 pub mod frame;
+pub mod layers;
 pub mod renderer;
-pub mod terminal;
\ No newline at end of file
+pub mod terminal;
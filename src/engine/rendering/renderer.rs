@@ -2,91 +2,166 @@ use std::{io, thread};
 use std::io::Write;
 use std::thread::JoinHandle;
 
-use anyhow::Result;
-use crossbeam_channel::{Receiver, Sender};
+use anyhow::{anyhow, Result};
+use crossbeam_channel::{Receiver, Sender, TrySendError};
 use crossterm::cursor::MoveTo;
 use crossterm::QueueableCommand;
 use crossterm::terminal::{Clear, ClearType};
 use crate::engine::rendering::frame;
 use crate::engine::rendering::frame::Frame;
+use crate::engine::rendering::terminal::Terminal;
+
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum FramePolicy {
+    // Every frame is queued, however far behind the terminal falls
+    Unbounded,
+    // At most n frames are queued, the oldest one is dropped when full
+    Bounded(usize),
+    // Only the newest frame is kept
+    #[default]
+    Latest,
+}
 
+impl FramePolicy {
+    fn channel(&self) -> (Sender<Frame>, Receiver<Frame>) {
+        match self {
+            FramePolicy::Unbounded => crossbeam_channel::unbounded(),
+            FramePolicy::Bounded(capacity) => crossbeam_channel::bounded((*capacity).max(1)),
+            FramePolicy::Latest => crossbeam_channel::bounded(1),
+        }
+    }
+}
 
 pub struct Renderer {
     frame_width: usize,
     frame_height: usize,
-    handle: Option<JoinHandle<()>>,
+    handle: Option<JoinHandle<Result<()>>>,
     rx: Receiver<Frame>,
     tx: Sender<Frame>
 }
 
 impl Default for Renderer {
     fn default() -> Self {
-        let (tx, rx) = crossbeam_channel::unbounded();
-        Self { tx, rx, handle: None, frame_width: 50, frame_height: 50 }
+        Renderer::new(50, 50, FramePolicy::default())
     }
 }
 
 impl Renderer {
 
-    pub fn new(width: usize, height: usize) -> Self {
-        let (tx, rx) = crossbeam_channel::unbounded();
+    pub fn new(width: usize, height: usize, policy: FramePolicy) -> Self {
+        let (tx, rx) = policy.channel();
         Self { tx, rx, handle: None, frame_width: width, frame_height: height }
     }
 
     pub fn run(&mut self) {
-        let rx = std::mem::replace(&mut self.rx, crossbeam_channel::never());
-        let width = self.frame_width.clone();
-        let height = self.frame_height.clone();
+        // We keep our own receiver around to evict stale frames when the channel is full
+        let rx = self.rx.clone();
+        let width = self.frame_width;
+        let height = self.frame_height;
 
         let render_handle = thread::spawn(move || {
             let mut previous_frame = frame::new_frame(width, height);
             let mut stdout = io::stdout();
+            let mut buffer = Vec::new();
 
-            Renderer::render(&mut stdout, &previous_frame, &previous_frame, true).unwrap();
+            Renderer::render(&mut stdout, &mut buffer, &previous_frame, &previous_frame, true)?;
 
             while let Ok(next_frame) = rx.recv() {
-                Renderer::render(&mut stdout, &previous_frame, &next_frame, false).unwrap();
+                Renderer::render(&mut stdout, &mut buffer, &previous_frame, &next_frame, false)?;
                 previous_frame = next_frame;
             }
+
+            Ok(())
         });
 
         self.handle = Some(render_handle);
     }
 
-    pub fn send(&self, frame: Frame) -> Result<()> {
-        self.tx.send(frame)?;
-        Ok(())
+    // Returns the number of queued frames that were dropped to make room for this one
+    pub fn send(&self, frame: Frame) -> Result<usize> {
+        // Our own receiver keeps the channel open, so a dead render thread has to be checked for
+        if self.handle.as_ref().is_some_and(|handle| handle.is_finished()) {
+            return Err(anyhow!("Render thread is no longer receiving frames."));
+        }
+
+        let mut frame = frame;
+        let mut dropped = 0;
+
+        loop {
+            match self.tx.try_send(frame) {
+                Ok(()) => return Ok(dropped),
+                Err(TrySendError::Full(rejected)) => {
+                    if self.rx.try_recv().is_ok() {
+                        dropped += 1;
+                    }
+                    frame = rejected;
+                }
+                Err(TrySendError::Disconnected(_)) => {
+                    return Err(anyhow!("Render thread is no longer receiving frames."));
+                }
+            }
+        }
     }
 
-    pub fn stop(mut self) {
+    // Returns whatever error made the render thread give up, if any
+    pub fn stop(self) -> Result<()> {
         if let Some(handle) = self.handle {
             drop(self.rx);
             drop(self.tx);
 
-            handle.join().unwrap();
-            self.handle = None;
+            return handle.join()
+                .map_err(|_| anyhow!("Render thread panicked."))?;
+        }
+
+        Ok(())
+    }
 
+    fn render<W: Write>(out: &mut W, buffer: &mut Vec<u8>, previous_frame: &Frame, next_frame: &Frame, force: bool) -> Result<()> {
+        // Once the terminal has been restored (e.g. by the panic hook) nothing should be drawn on it
+        if !Terminal::is_active() {
+            return Ok(());
         }
+
+        buffer.clear();
+        Renderer::encode(buffer, previous_frame, next_frame, force)?;
+
+        // The whole frame goes out in a single write
+        out.write_all(buffer)?;
+        out.flush()?;
+
+        Ok(())
     }
 
-    fn render(stdout: &mut io::Stdout, previous_frame: &Frame, next_frame: &Frame, force: bool) -> Result<()> {
+    // Encodes the changed cells of a frame row by row, moving the cursor only at the start of a run
+    pub fn encode(buffer: &mut Vec<u8>, previous_frame: &Frame, next_frame: &Frame, force: bool) -> Result<()> {
         if force {
-            stdout.queue(Clear(ClearType::All))?;
+            buffer.queue(Clear(ClearType::All))?;
         }
-        for (x, col) in next_frame.iter().enumerate() {
-            for (y, content) in col.iter().enumerate() {
-                let content_changed = previous_frame[x][y] != *content;
-                let char = char::from_u32(*content).unwrap();
-                if content_changed || force {
-                    stdout.queue(MoveTo(x as u16, y as u16))?;
-                    print!("{}", char);
+
+        let width = next_frame.len();
+        let height = next_frame.first().map_or(0, |col| col.len());
+        let mut cursor: Option<(usize, usize)> = None;
+        let mut utf8 = [0; 4];
+
+        for y in 0..height {
+            for x in 0..width {
+                let content = next_frame[x][y];
+                if !force && previous_frame[x][y] == content {
+                    continue;
+                }
+
+                if cursor != Some((x, y)) {
+                    buffer.queue(MoveTo(x as u16, y as u16))?;
                 }
 
+                let char = char::from_u32(content)
+                    .ok_or_else(|| anyhow!("Invalid character {:#x} at ({}, {}).", content, x, y))?;
+                buffer.extend_from_slice(char.encode_utf8(&mut utf8).as_bytes());
+                cursor = Some((x + 1, y));
             }
         }
 
-        stdout.flush()?;
-
         Ok(())
     }
-}
\ No newline at end of file
+}
@@ -1,35 +1,81 @@
 use std::io;
-use std::io::Stdout;
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
 
 use anyhow::Result;
-use crossterm::{cursor, ExecutableCommand, terminal};
+use crossterm::{cursor, execute, terminal};
+use crossterm::event::{KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags};
 
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static KEYBOARD_ENHANCED: AtomicBool = AtomicBool::new(false);
+static PANIC_HOOK: Once = Once::new();
+
+// Holds the terminal in raw mode on the alternate screen until dropped
 pub struct Terminal {
-    stdout: Stdout,
+    _private: (),
 }
 
-impl Default for Terminal {
-    fn default() -> Self {
-        Self {
-            stdout: io::stdout()
+impl Terminal {
+    pub fn setup() -> Result<Terminal> {
+        Terminal::install_panic_hook();
+
+        // Created first so a failure halfway through setup is still undone on drop
+        let terminal = Terminal { _private: () };
+        ACTIVE.store(true, Ordering::SeqCst);
+
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+
+        // Needed to tell presses from repeats and releases, not every terminal supports it
+        if terminal::supports_keyboard_enhancement().unwrap_or(false) {
+            execute!(io::stdout(), PushKeyboardEnhancementFlags(
+                KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+            ))?;
+            KEYBOARD_ENHANCED.store(true, Ordering::SeqCst);
         }
+
+        Ok(terminal)
     }
-}
 
-impl Terminal {
-    pub fn setup(&mut self) -> Result<()> {
-        terminal::enable_raw_mode()?;
-        self.stdout.execute(terminal::EnterAlternateScreen)?;
-        self.stdout.execute(cursor::Hide)?;
+    pub fn cleanup(self) -> Result<()> {
+        Terminal::restore()
+    }
 
-        Ok(())
+    pub fn is_active() -> bool {
+        ACTIVE.load(Ordering::SeqCst)
     }
 
-    pub fn cleanup(&mut self) -> Result<()> {
-        self.stdout.execute(cursor::Show)?;
-        self.stdout.execute(terminal::LeaveAlternateScreen)?;
-        terminal::disable_raw_mode()?;
+    pub fn is_keyboard_enhanced() -> bool {
+        KEYBOARD_ENHANCED.load(Ordering::SeqCst)
+    }
+
+    fn restore() -> Result<()> {
+        if ACTIVE.swap(false, Ordering::SeqCst) {
+            if KEYBOARD_ENHANCED.swap(false, Ordering::SeqCst) {
+                execute!(io::stdout(), PopKeyboardEnhancementFlags)?;
+            }
+            execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen)?;
+            terminal::disable_raw_mode()?;
+        }
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    // Restores the terminal before the panic message is printed, otherwise it is lost with the alternate screen
+    fn install_panic_hook() {
+        PANIC_HOOK.call_once(|| {
+            let previous_hook = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                let _ = Terminal::restore();
+                previous_hook(info);
+            }));
+        });
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = Terminal::restore();
+    }
+}
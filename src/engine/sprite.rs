@@ -11,6 +11,7 @@ pub struct Sprite {
     pub visible: bool,
     pub translation: Vector2D<usize>,
     pub layer: i32,
+    pub render_layer: Option<String>,
 }
 
 fn transpose<T>(v: Vec<Vec<T>>) -> Vec<Vec<T>>
@@ -36,42 +37,45 @@ impl Sprite {
             data: transpose(data),
             translation: Vector2D::new(0, 0),
             layer: 0,
+            render_layer: None,
         }
     }
 
     pub fn from_file<P: AsRef<Path>>(label: &str, path: P) -> Result<Sprite, Error> {
         if let Ok(content) = read_to_string(path) {
-            let lines: Vec<&str> = content.lines().collect();
-            let height = lines.len();
-            let width = lines.iter()
-                .map(|line| line.len())
-                .max()
-                .unwrap();
+            Sprite::from_text(label, &content)
+        } else {
+            Err(anyhow!("Could not load sprite from file."))
+        }
 
-            let mut data = Vec::with_capacity(height);
-            for line in lines.iter() {
-                let mut row = vec![' '; width];
-                for y in 0..width {
-                    if let Some(char) = line.chars().nth(y) {
-                        row[y] = char;
-                    }
-                }
-                data.push(row);
-            }
-            println!("{}", data.is_empty());
+    }
 
-            Ok(Self {
-                label: label.to_string(),
-                visible: true,
-                data: transpose(data),
-                translation: Vector2D::new(0, 0),
-                layer: 0,
-            })
+    // Multi-line sprite, shorter lines are padded with spaces
+    pub fn from_text(label: &str, text: &str) -> Result<Sprite, Error> {
+        let lines: Vec<&str> = text.lines().collect();
+        let height = lines.len();
+        let width = lines.iter()
+            .map(|line| line.chars().count())
+            .max()
+            .ok_or_else(|| anyhow!("Sprite '{}' is empty.", label))?;
 
-        } else {
-            Err(anyhow!("Could not load sprite from file."))
+        let mut data = Vec::with_capacity(height);
+        for line in lines.iter() {
+            let mut row = vec![' '; width];
+            for (y, char) in line.chars().enumerate() {
+                row[y] = char;
+            }
+            data.push(row);
         }
 
+        Ok(Self {
+            label: label.to_string(),
+            visible: true,
+            data: transpose(data),
+            translation: Vector2D::new(0, 0),
+            layer: 0,
+            render_layer: None,
+        })
     }
 
     pub fn with_layer(mut self, layer: i32) -> Self {
@@ -79,6 +83,11 @@ impl Sprite {
         self
     }
 
+    pub fn on_layer(mut self, render_layer: &str) -> Self {
+        self.render_layer = Some(render_layer.to_string());
+        self
+    }
+
     pub fn with_translation(mut self, translation: Vector2D<usize>) -> Self {
         self.translation = translation;
         self
@@ -100,7 +109,10 @@ impl Drawable for Sprite {
                 for (y, char) in col.iter().enumerate() {
                     let x_pos = self.translation.x + x;
                     let y_post = self.translation.y + y;
-                    frame[x_pos][y_post] = *char as u32
+                    // Whatever falls outside the frame is clipped
+                    if let Some(cell) = frame.get_mut(x_pos).and_then(|col| col.get_mut(y_post)) {
+                        *cell = *char as u32
+                    }
                 }
             }
         }
@@ -1,2 +1,5 @@
+pub mod ai;
+pub mod controller;
 pub mod engine;
+pub mod protocol;
 pub mod tictactoe;
\ No newline at end of file
@@ -1,154 +1,91 @@
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
 use anyhow::Result;
 use crossterm::event::KeyCode;
-use vector2d::Vector2D;
-use tic_tac_toe::{
-    engine::{Engine, sprite::Sprite}
-};
-use tic_tac_toe::engine::window::Window;
-use tic_tac_toe::tictactoe::coordinates::BoardCoordinates;
+use tic_tac_toe::ai::Ai;
+use tic_tac_toe::ai::computer::{ComputerPlayer, Difficulty};
+use tic_tac_toe::ai::mcts::{self, MctsPlayer};
+use tic_tac_toe::controller::bot::{Bot, DEFAULT_TIME_LIMIT};
+use tic_tac_toe::controller::computer::Computer;
+use tic_tac_toe::controller::keyboard::Keyboard;
+use tic_tac_toe::controller::network::NetworkPeer;
+use tic_tac_toe::controller::scripted::Scripted;
+use tic_tac_toe::controller::Controller;
+use tic_tac_toe::engine::keyboard::bindings::KeyBindings;
+use tic_tac_toe::protocol;
 use tic_tac_toe::tictactoe::game::TicTacToe;
-use tic_tac_toe::tictactoe::movement::MoveDirection;
-use tic_tac_toe::tictactoe::player::Player;
-
-const GRID_HEIGHT: usize = 5;
-const GRID_WIDTH: usize = 11;
+use tic_tac_toe::tictactoe::rules::Rules;
 
-const Y_GRID: usize = 2;
-const Y_INSTRUCTIONS: usize = Y_GRID + GRID_HEIGHT + 1;
-const Y_TURN: usize = 0;
-const Y_FPS: usize = Y_INSTRUCTIONS + 2;
+use crate::options::{Mode, Opponent, Options, Peer};
 
-fn main() -> Result<()> {
+mod modes;
+mod options;
 
-    let grid = Sprite::from_file("grid", "sprites/grid")?
-        .with_translation(Vector2D::new(0, Y_GRID))
-        .with_layer(0);
+const BINDINGS_PATH: &str = "config/bindings";
+// Long enough to follow a replayed game move by move
+const REPLAY_DELAY: Duration = Duration::from_millis(700);
 
-    let instructions = Sprite::from_string("instructions", "Q: Quit, Space: Play, Arrows: Move")
-        .with_translation(Vector2D::new(0, Y_INSTRUCTIONS))
-        .with_layer(0);
-
-    Engine::new(Window::new(50, 50))
-        .set_fps(60)
-        .with_sprite("grid", grid)
-        .with_sprite("instructions", instructions)
-        .with_logic(base_logic)
-        .with_logic(game_movement)
-        .with_logic(cursor_logic)
-        .with_logic(markers_logic)
-        .with_logic(win_lose_logic)
-        .with_logic(fps_counter)
-        .run(TicTacToe::default())?;
+fn main() -> Result<()> {
+    let options = Options::from_args()?;
 
-    Ok(())
-}
+    let key_bindings = if Path::new(BINDINGS_PATH).exists() {
+        KeyBindings::from_file(BINDINGS_PATH)?
+    } else {
+        KeyBindings::preset("arrows")?
+            .bind("toggle_debug", KeyCode::F(3))
+            .bind("enter_coordinates", KeyCode::Char(':'))
+            .bind("toggle_mark", KeyCode::Tab)
+            .bind("layer_up", KeyCode::PageUp)
+            .bind("layer_down", KeyCode::PageDown)
+            .bind("undo", KeyCode::Char('u'))
+            .bind("hint", KeyCode::Char('?'))
+            .bind("toggle_threats", KeyCode::Char('t'))
+            .bind("review", KeyCode::Char('r'))
+    };
 
-fn base_logic<T>(engine: &mut Engine<T>, _: &mut T) {
-    for key in engine.get_pressed_keys() {
-        match key {
-            KeyCode::Esc | KeyCode::Char('q') => {
-                engine.stop();
-            }
-            _ => {}
+    let computer = options.ai.map(|opponent| -> Box<dyn Ai> {
+        let time_limit = options.move_time.unwrap_or(mcts::DEFAULT_TIME_LIMIT);
+        match (opponent, options.seed) {
+            (Opponent::Computer(difficulty), Some(seed)) => Box::new(ComputerPlayer::new(difficulty).with_seed(seed)),
+            (Opponent::Computer(difficulty), None) => Box::new(ComputerPlayer::new(difficulty)),
+            (Opponent::Mcts, Some(seed)) => Box::new(MctsPlayer::new().with_time_limit(time_limit).with_seed(seed)),
+            (Opponent::Mcts, None) => Box::new(MctsPlayer::new().with_time_limit(time_limit)),
         }
-    }
-}
+    });
 
-fn game_movement(engine: &mut Engine<TicTacToe>, game: &mut TicTacToe) {
-    for key in engine.get_pressed_keys() {
-        match key {
-            KeyCode::Left => {
-                game.move_cursor(MoveDirection::LEFT);
-            }
-            KeyCode::Right => {
-                game.move_cursor(MoveDirection::RIGHT);
-            }
-            KeyCode::Up => {
-                game.move_cursor(MoveDirection::UP);
-            }
-            KeyCode::Down => {
-                game.move_cursor(MoveDirection::DOWN);
-            }
-            KeyCode::Enter | KeyCode::Char(' ') => {
-                game.play();
-            }
-            _ => {}
-        }
+    if options.serve {
+        let mut ai = computer.unwrap_or_else(|| Box::new(ComputerPlayer::new(Difficulty::default())));
+        return protocol::serve(ai.as_mut(), io::stdin().lock(), io::stdout().lock());
     }
-}
-
-fn cursor_logic(engine: &mut Engine<TicTacToe>, game: &mut TicTacToe) {
-    if !game.is_game_over() {
-        let cursor_str = if game.turn == Player::ONE {"x"} else {"o"};
-        let mut sprite = Sprite::from_string("cursor", cursor_str);
-        let board = engine.get_sprite("grid").unwrap();
-        let cursor_coords = game.cursor_position.to_frame_coordinates(&board.translation);
-        let translate = Vector2D::new(cursor_coords.x, cursor_coords.y);
 
-        sprite.translation = translate;
-        sprite.layer = 1;
+    let game = match (options.mode, options.rules) {
+        (Mode::Ultimate, _) => return modes::ultimate::run(key_bindings),
+        (Mode::Standard, Rules::Notakto) if !options.gravity => return modes::notakto::run(options.boards, key_bindings),
+        (Mode::Cube, rules) => TicTacToe::cube(rules, options.size),
+        (Mode::Standard, rules) => options.board.new_game(rules, options.gravity),
+    };
 
-        if game.turn == Player::ONE {
-            engine.remove_sprite("p2");
-            engine.insert_sprite("p1", sprite);
-        } else if game.turn == Player::TWO {
-            engine.remove_sprite("p1");
-            engine.insert_sprite("p2", sprite);
+    // X is always played here unless we joined someone else's game
+    let controllers: [Box<dyn Controller>; 2] = match (&options.peer, &options.bot, &options.replay, computer) {
+        (Some(Peer::Host(address)), _, _, _) => {
+            println!("Waiting for a player to connect to {}...", address);
+            [Box::new(Keyboard), Box::new(NetworkPeer::host(address.as_str(), &game)?)]
         }
-    }
-}
-
-fn markers_logic(engine: &mut Engine<TicTacToe>, game: &mut TicTacToe) {
-    for (x, col) in game.state.iter().enumerate() {
-        for (y, cell) in col.iter().enumerate() {
-            if *cell == Player::ONE || *cell == Player::TWO {
-                let marker_str = if *cell == Player::ONE {"X"} else {"O"};
-                let mut sprite = Sprite::from_string("marker", marker_str);
-                let board = engine.get_sprite("grid").unwrap();
-                let translate = BoardCoordinates::new(x, y).to_frame_coordinates(&board.translation);
-
-                sprite.translation = Vector2D::new(translate.x, translate.y);
-                sprite.layer = 1;
-
-                engine.insert_sprite(format!("{}-{}", x, y).as_str(), sprite);
-            }
+        (Some(Peer::Connect(address)), _, _, _) => {
+            [Box::new(NetworkPeer::connect(address.as_str(), &game)?), Box::new(Keyboard)]
         }
-    }
-}
-
-fn win_lose_logic(engine: &mut Engine<TicTacToe>, game: &mut TicTacToe) {
-
-    let str = if game.winner != Player::NONE {
-        format!("Winner: {:?}", game.winner)
-    } else if game.is_grid_filled() {
-        "Draw".to_string()
-    } else {
-        format!("Turn: Player {:?}", game.turn)
+        (None, Some(command), _, _) => {
+            let bot = Bot::launch(command)?.with_time_limit(options.move_time.unwrap_or(DEFAULT_TIME_LIMIT));
+            [Box::new(Keyboard), Box::new(bot)]
+        }
+        (None, None, Some(path), _) => {
+            Scripted::replay(path, &game)?.map(|side| -> Box<dyn Controller> { Box::new(side.with_delay(REPLAY_DELAY)) })
+        }
+        (None, None, None, Some(ai)) => [Box::new(Keyboard), Box::new(Computer::new(ai))],
+        (None, None, None, None) => [Box::new(Keyboard), Box::new(Keyboard)],
     };
 
-    engine.remove_sprite("win-lose");
-
-    let mut sprite = Sprite::from_string("win-lose", str.as_str());
-    sprite.layer = 1;
-    sprite.translation = Vector2D::new(0, Y_TURN);
-
-    engine.insert_sprite("win-lose", sprite);
+    modes::board::run(game, controllers, key_bindings)
 }
-
-fn fps_counter<T>(engine: &mut Engine<T>, _: &mut T) {
-    if engine.delta.as_millis() > 0.0 as u128 {
-        let fps = 1000_u128 / engine.delta.as_millis();
-        let str = format!("FPS: {}", fps);
-
-        engine.remove_sprite("fps");
-        let mut sprite = Sprite::from_string("fps", str.as_str());
-        sprite.layer = 1;
-        sprite.translation = Vector2D::new(0, Y_FPS);
-        engine.insert_sprite("fps", sprite);
-    }
-}
-
-
-
-
-
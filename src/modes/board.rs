@@ -0,0 +1,689 @@
+use std::time::Duration;
+
+use anyhow::{Error, Result};
+use crossterm::event::KeyCode;
+use vector2d::Vector2D;
+use tic_tac_toe::ai::Ai;
+use tic_tac_toe::ai::analysis::{analyse_until, Analysis, Verdict};
+use tic_tac_toe::ai::computer::{ComputerPlayer, Difficulty};
+use tic_tac_toe::controller::keyboard::Keyboard;
+use tic_tac_toe::controller::Controller;
+use tic_tac_toe::engine::Engine;
+use tic_tac_toe::engine::jobs::Job;
+use tic_tac_toe::engine::keyboard::bindings::KeyBindings;
+use tic_tac_toe::engine::sprite::Sprite;
+use tic_tac_toe::engine::window::Window;
+use tic_tac_toe::tictactoe::coordinates::BoardCoordinates;
+use tic_tac_toe::tictactoe::cursor::Cursor;
+use tic_tac_toe::tictactoe::game::TicTacToe;
+use tic_tac_toe::tictactoe::movement::MoveDirection;
+use tic_tac_toe::tictactoe::moves::{GameStatus, Move, MoveError};
+use tic_tac_toe::tictactoe::player::Player;
+use tic_tac_toe::tictactoe::solver::Outcome;
+
+use crate::modes::{base_logic, fps_counter, grid_height, grid_sprite, grid_width, mark_str, text_sprite, TextEntry, GRID_SPACING, Y_GRID, Y_TURN};
+
+const DROP_TIME_PER_CELL: Duration = Duration::from_millis(40);
+const NOTICE_TIME: Duration = Duration::from_secs(5);
+
+// A mark falling down its column in gravity mode
+struct DropAnimation {
+    target: BoardCoordinates,
+    mark: Player,
+    elapsed: Duration,
+}
+
+// A message shown under the instructions for a while
+struct Notice {
+    text: String,
+    elapsed: Duration,
+}
+
+impl Notice {
+    fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into(), elapsed: Duration::ZERO }
+    }
+}
+
+// The best move for the side to move, worked out in the background when asked for
+struct Hint {
+    // Moves played when it was asked for, it only applies to that position
+    position: Vec<Move>,
+    job: Option<Job<Option<Move>>>,
+    mv: Option<Move>,
+}
+
+// Stepping through a finished game with every move graded
+struct Review {
+    job: Option<Job<Option<Analysis>>>,
+    analysis: Option<Analysis>,
+    // The board after the first `ply` moves
+    ply: usize,
+    position: TicTacToe,
+}
+
+struct App {
+    game: TicTacToe,
+    cursor: Cursor,
+    // Who plays X and who plays O
+    controllers: [Box<dyn Controller>; 2],
+    // Move picked with the cursor this frame, for the side to move to take or ignore
+    picked: Option<Move>,
+    // Why a side is now played from the keyboard, or what went wrong
+    notice: Option<Notice>,
+    // Cell typed so far in coordinate entry mode, e.g. "b2"
+    coordinate_entry: Option<String>,
+    drop: Option<DropAnimation>,
+    hint: Option<Hint>,
+    // Cells the player to move has to block, or under misère stay away from, while shown
+    threats: Option<Vec<BoardCoordinates>>,
+    review: Option<Review>,
+}
+
+impl App {
+    fn side(&self) -> Option<usize> {
+        match self.game.turn() {
+            Player::ONE => Some(0),
+            Player::TWO => Some(1),
+            Player::NONE => None,
+        }
+    }
+
+    fn is_local_turn(&self) -> bool {
+        self.side().is_some_and(|side| self.controllers[side].is_local())
+    }
+
+    // Picks the cell under the cursor
+    fn play(&mut self) {
+        if !self.is_local_turn() {
+            return;
+        }
+        if let Some(target) = self.cursor.target(&self.game) {
+            self.picked = Some(Move { coords: target, mark: self.game.current_mark() });
+        }
+    }
+
+    fn play_move(&mut self, mv: Move) -> Result<(), MoveError> {
+        self.game.play_move(&mv)?;
+        for side in 0..self.controllers.len() {
+            if let Err(error) = self.controllers[side].move_played(&self.game, &mv) {
+                self.hand_over(side, error);
+            }
+        }
+
+        self.update_threats();
+        self.cursor.follow(&self.game);
+        if self.game.gravity() {
+            self.drop = Some(DropAnimation { target: mv.coords, mark: mv.mark, elapsed: Duration::ZERO });
+        }
+        Ok(())
+    }
+
+    // A side that can't go on is played from the keyboard instead
+    fn hand_over(&mut self, side: usize, error: Error) {
+        let player = if side == 0 { Player::ONE } else { Player::TWO };
+        self.notice = Some(Notice::new(format!("Player {:?} left: {}", player, error)));
+        self.controllers[side] = Box::new(Keyboard);
+    }
+
+    // Takes back moves until it is a local player's turn again
+    fn undo(&mut self) {
+        let controllers = &self.controllers;
+        if !controllers.iter().all(|controller| controller.allows_undo())
+            || !controllers.iter().any(|controller| controller.is_local()) {
+            return;
+        }
+
+        self.drop = None;
+        while self.game.undo().is_some() && !self.is_local_turn() {}
+        for controller in self.controllers.iter_mut() {
+            controller.undone(&self.game);
+        }
+        self.update_threats();
+        self.cursor.follow(&self.game);
+    }
+
+    fn update_threats(&mut self) {
+        if self.threats.is_some() {
+            // Lines the opponent could complete need blocking. Where completing a line loses,
+            // it is the player's own lines that must be left alone.
+            let player = self.game.turn().clone();
+            let threatened = if self.game.rules().winner_of_line(&player) == player { player.other() } else { player };
+            let mut cells: Vec<BoardCoordinates> = self.game.completing_moves(&threatened).into_iter()
+                .map(|mv| mv.coords)
+                .collect();
+            // Both marks of the same cell when players pick their mark
+            cells.dedup();
+            self.threats = Some(cells);
+        }
+    }
+}
+
+impl TextEntry for App {
+    fn is_entering_text(&self) -> bool {
+        self.coordinate_entry.is_some()
+    }
+}
+
+// The layers of a 3D board are drawn side by side
+fn layer_offset(game: &TicTacToe, z: usize) -> Vector2D<usize> {
+    Vector2D::new(z * (grid_width(game.state().width()) + GRID_SPACING), Y_GRID)
+}
+
+fn y_layer_labels(game: &TicTacToe) -> usize {
+    Y_GRID + grid_height(game.state().height())
+}
+
+fn y_instructions(game: &TicTacToe) -> usize {
+    y_layer_labels(game) + 2
+}
+
+fn has_keypad(game: &TicTacToe) -> bool {
+    !game.gravity() && game.state().width() == 3 && game.state().height() == 3
+}
+
+pub fn run(game: TicTacToe, controllers: [Box<dyn Controller>; 2], key_bindings: KeyBindings) -> Result<()> {
+    let (width, height, depth) = (game.state().width(), game.state().height(), game.state().depth());
+    let y_instructions = y_instructions(&game);
+    let window_width = layer_offset(&game, depth).x.max(50);
+    // The review lines are the lowest, 7 to 10 lines below the instructions
+    let window_height = (y_instructions + 11).max(50);
+
+    let mut engine = Engine::new(Window::new(window_width, window_height))
+        .set_fps(60)
+        .with_key_bindings(key_bindings)
+        .with_render_layer("debug", 10);
+
+    for z in 0..depth {
+        let label = format!("grid-{}", z);
+        let offset = layer_offset(&game, z);
+        engine = engine.with_sprite(&label, grid_sprite(&label, width, height, offset.x, offset.y)?);
+    }
+
+    let mut instructions = vec![
+        "Q: Quit, Space: Play, Arrows: Move, F3: Debug",
+        if game.gravity() {
+            "Left/Right or 1-9: Pick a column"
+        } else if has_keypad(&game) {
+            "1-9: Place on cell, :: Enter a cell like b2"
+        } else {
+            ":: Enter a cell like b2"
+        },
+        "U: Undo, ?: Hint, T: Threats, R: Review the game",
+    ];
+    if depth > 1 {
+        instructions.push("PgUp/PgDn: Layers, :: Enter a cell like b2.3");
+    }
+    if game.rules().can_choose_mark() {
+        instructions.push("Tab: Switch between X and O");
+    }
+    for (line, text) in instructions.iter().enumerate() {
+        let label = format!("instructions-{}", line);
+        let sprite = Sprite::from_string(&label, text)
+            .with_translation(Vector2D::new(0, y_instructions + line))
+            .with_layer(0);
+        engine = engine.with_sprite(&label, sprite);
+    }
+
+    engine
+        .with_logic(base_logic)
+        .with_logic(game_movement)
+        .with_logic(cell_selection)
+        .with_logic(controller_logic)
+        .with_logic(coordinate_entry_logic)
+        .with_logic(cursor_logic)
+        .with_logic(markers_logic)
+        .with_logic(hint_logic)
+        .with_logic(threats_logic)
+        .with_logic(review_logic)
+        .with_logic(drop_animation_logic)
+        .with_logic(layer_labels_logic)
+        .with_logic(win_lose_logic)
+        .with_logic(notice_logic)
+        .with_logic(fps_counter)
+        .run(App {
+            game,
+            cursor: Cursor::default(),
+            controllers,
+            picked: None,
+            notice: None,
+            coordinate_entry: None,
+            drop: None,
+            hint: None,
+            threats: None,
+            review: None,
+        })
+}
+
+fn game_movement(engine: &mut Engine<App>, app: &mut App) {
+    if app.coordinate_entry.is_some() {
+        return;
+    }
+
+    let (game, cursor) = (&mut app.game, &mut app.cursor);
+    if engine.is_action_pressed("move_left") {
+        cursor.move_towards(game, MoveDirection::LEFT);
+    }
+    if engine.is_action_pressed("move_right") {
+        cursor.move_towards(game, MoveDirection::RIGHT);
+    }
+    if engine.is_action_pressed("move_up") {
+        cursor.move_towards(game, MoveDirection::UP);
+    }
+    if engine.is_action_pressed("move_down") {
+        cursor.move_towards(game, MoveDirection::DOWN);
+    }
+    if engine.is_action_pressed("layer_up") {
+        cursor.move_towards(game, MoveDirection::ABOVE);
+    }
+    if engine.is_action_pressed("layer_down") {
+        cursor.move_towards(game, MoveDirection::BELOW);
+    }
+    if engine.is_action_just_pressed("toggle_mark") {
+        game.toggle_mark();
+    }
+    if engine.is_action_just_pressed("place") {
+        app.play();
+    }
+    if engine.is_action_just_pressed("undo") {
+        app.undo();
+    }
+    if engine.is_action_just_pressed("toggle_threats") {
+        app.threats = match app.threats {
+            Some(_) => None,
+            None => Some(vec![]),
+        };
+        app.update_threats();
+    }
+}
+
+// Runs after the other input logic so the keys that end coordinate entry aren't also seen as actions
+fn cell_selection(engine: &mut Engine<App>, app: &mut App) {
+    let events = engine.keys().pressed().to_vec();
+
+    if let Some(entry) = app.coordinate_entry.as_mut() {
+        for event in events {
+            match event.code {
+                KeyCode::Char(char) if char.is_ascii_alphanumeric() || char == '.' => entry.push(char),
+                KeyCode::Backspace => {
+                    entry.pop();
+                }
+                KeyCode::Enter => {
+                    // Cells without a layer, like b2, are on the layer the cursor is on
+                    if let Some(mut coords) = BoardCoordinates::from_notation(entry) {
+                        if !entry.contains('.') {
+                            coords.z = app.cursor.position().z;
+                        }
+                        if app.cursor.select(&app.game, coords) {
+                            app.play();
+                        }
+                    }
+                    app.coordinate_entry = None;
+                    break;
+                }
+                KeyCode::Esc => {
+                    app.coordinate_entry = None;
+                    break;
+                }
+                _ => {}
+            }
+        }
+        return;
+    }
+
+    if engine.is_action_just_pressed("enter_coordinates") {
+        app.coordinate_entry = Some(String::new());
+        return;
+    }
+
+    for event in events {
+        let digit = match event.code {
+            KeyCode::Char(char) => char.to_digit(10),
+            _ => None,
+        };
+        // Digits pick a column with gravity, and a cell on the classic board
+        let coords = match digit {
+            Some(digit) if app.game.gravity() && digit >= 1 => Some(BoardCoordinates::new(digit as usize - 1, 0)),
+            Some(digit) if has_keypad(&app.game) => BoardCoordinates::from_keypad(digit),
+            _ => None,
+        };
+        if let Some(coords) = coords {
+            if app.cursor.select(&app.game, coords.with_z(app.cursor.position().z)) {
+                app.play();
+            }
+        }
+    }
+}
+
+// Asks the side to move for its move
+fn controller_logic(_engine: &mut Engine<App>, app: &mut App) {
+    let picked = app.picked.take();
+    // Let the previous mark land first
+    if app.drop.is_some() || app.game.is_game_over() {
+        return;
+    }
+    let Some(side) = app.side() else {
+        return;
+    };
+
+    match app.controllers[side].poll_move(&app.game, picked.as_ref()) {
+        Ok(Some(mv)) => {
+            // Cells that can't be played are ignored at the keyboard, anyone else has to get it right
+            if let Err(error) = app.play_move(mv) {
+                if !app.controllers[side].is_local() {
+                    app.hand_over(side, error.into());
+                }
+            }
+        }
+        Ok(None) => {}
+        Err(error) => app.hand_over(side, error),
+    }
+}
+
+fn coordinate_entry_logic(engine: &mut Engine<App>, app: &mut App) {
+    match &app.coordinate_entry {
+        Some(entry) => {
+            let y = y_instructions(&app.game) + 5;
+            let sprite = text_sprite("entry", format!("Cell: {}_", entry).as_str(), 0, y);
+            engine.insert_sprite("entry", sprite);
+        }
+        None => engine.remove_sprite("entry"),
+    }
+}
+
+fn cursor_logic(engine: &mut Engine<App>, app: &mut App) {
+    let game = &app.game;
+    // It would stand on cells the review has emptied
+    if app.review.is_some() {
+        engine.remove_sprite("p1");
+        engine.remove_sprite("p2");
+        return;
+    }
+    if !game.is_game_over() {
+        let cursor_str = if game.current_mark() == Player::ONE {"x"} else {"o"};
+        let mut sprite = Sprite::from_string("cursor", cursor_str);
+        let offset = layer_offset(game, app.cursor.position().z);
+        // With gravity the cursor shows where the mark would land
+        let cursor_position = if game.gravity() {
+            app.cursor.target(game).unwrap_or(app.cursor.position())
+        } else {
+            app.cursor.position()
+        };
+        let cursor_coords = cursor_position.to_frame_coordinates(&offset);
+        let translate = Vector2D::new(cursor_coords.x, cursor_coords.y);
+
+        sprite.translation = translate;
+        sprite.layer = 1;
+
+        if *game.turn() == Player::ONE {
+            engine.remove_sprite("p2");
+            engine.insert_sprite("p1", sprite);
+        } else if *game.turn() == Player::TWO {
+            engine.remove_sprite("p1");
+            engine.insert_sprite("p2", sprite);
+        }
+    }
+}
+
+fn markers_logic(engine: &mut Engine<App>, app: &mut App) {
+    let game = app.review.as_ref().map_or(&app.game, |review| &review.position);
+    let falling = app.drop.as_ref().map(|drop| drop.target);
+    for (coords, cell) in game.state().cells() {
+        if falling == Some(coords) {
+            continue;
+        }
+        let label = format!("{}-{}-{}", coords.z, coords.x, coords.y);
+        if let Some(marker_str) = mark_str(cell) {
+            let mut sprite = Sprite::from_string("marker", marker_str);
+            let translate = coords.to_frame_coordinates(&layer_offset(game, coords.z));
+
+            sprite.translation = Vector2D::new(translate.x, translate.y);
+            sprite.layer = 1;
+
+            engine.insert_sprite(&label, sprite);
+        } else {
+            // Undone moves leave their marker behind otherwise
+            engine.remove_sprite(&label);
+        }
+    }
+}
+
+fn drop_animation_logic(engine: &mut Engine<App>, app: &mut App) {
+    let Some(drop) = app.drop.as_mut() else {
+        engine.remove_sprite("falling");
+        return;
+    };
+
+    drop.elapsed += engine.delta;
+    let row = (drop.elapsed.as_millis() / DROP_TIME_PER_CELL.as_millis()) as usize;
+    if row > drop.target.y {
+        app.drop = None;
+        engine.remove_sprite("falling");
+        return;
+    }
+
+    let coords = BoardCoordinates { y: row, ..drop.target }
+        .to_frame_coordinates(&layer_offset(&app.game, drop.target.z));
+    let marker_str = mark_str(&drop.mark).unwrap_or(" ");
+    engine.insert_sprite("falling", text_sprite("falling", marker_str, coords.x, coords.y));
+}
+
+fn hint_logic(engine: &mut Engine<App>, app: &mut App) {
+    if engine.is_action_just_pressed("hint") && !app.is_entering_text() && app.is_local_turn() && !app.game.is_game_over() {
+        let game = app.game.clone();
+        let job = engine.spawn_job(move |token| {
+            ComputerPlayer::new(Difficulty::Hard).choose_move_until(&game, &|| token.is_cancelled())
+        });
+        app.hint = Some(Hint { position: app.game.history().to_vec(), job: Some(job), mv: None });
+    }
+
+    // Dropping the job of a hint that no longer applies cancels it
+    if app.hint.as_ref().is_some_and(|hint| hint.position != app.game.history()) {
+        app.hint = None;
+    }
+    if let Some(hint) = app.hint.as_mut() {
+        match hint.job.as_ref().map(|job| job.poll()) {
+            Some(Ok(Some(mv))) => {
+                hint.mv = mv;
+                hint.job = None;
+            }
+            Some(Err(_)) => app.hint = None,
+            _ => {}
+        }
+    }
+
+    match app.hint.as_ref().and_then(|hint| hint.mv.as_ref()) {
+        Some(mv) => {
+            let coords = mv.coords.to_frame_coordinates(&layer_offset(&app.game, mv.coords.z));
+            let mark = if mv.mark == Player::ONE {"x"} else {"o"};
+            engine.insert_sprite("hint", text_sprite("hint", &format!("[{}]", mark), coords.x - 1, coords.y).with_layer(0));
+        }
+        None => engine.remove_sprite("hint"),
+    }
+}
+
+// Marks the cells the player to move has to block
+fn threats_logic(engine: &mut Engine<App>, app: &mut App) {
+    let game = &app.game;
+    let hint = app.hint.as_ref().and_then(|hint| hint.mv.as_ref()).map(|mv| mv.coords);
+    for (coords, _) in game.state().cells() {
+        let label = format!("threat-{}-{}-{}", coords.z, coords.x, coords.y);
+        let is_threat = app.threats.as_ref().is_some_and(|threats| threats.contains(&coords));
+        if is_threat && hint != Some(coords) {
+            let frame = coords.to_frame_coordinates(&layer_offset(game, coords.z));
+            engine.insert_sprite(&label, text_sprite(&label, "! !", frame.x - 1, frame.y).with_layer(0));
+        } else {
+            engine.remove_sprite(&label);
+        }
+    }
+}
+
+fn review_logic(engine: &mut Engine<App>, app: &mut App) {
+    if engine.is_action_just_pressed("review") && !app.is_entering_text() {
+        app.review = match app.review {
+            Some(_) => None,
+            None if app.game.is_game_over() => {
+                let game = app.game.clone();
+                Some(Review {
+                    job: Some(engine.spawn_job(move |token| analyse_until(&game, &|| token.is_cancelled()))),
+                    analysis: None,
+                    ply: app.game.history().len(),
+                    position: app.game.clone(),
+                })
+            }
+            None => None,
+        };
+    }
+    // Taking moves back reopens the game
+    if !app.game.is_game_over() {
+        app.review = None;
+    }
+
+    let Some(review) = app.review.as_mut() else {
+        for line in 0..4 {
+            engine.remove_sprite(&format!("review-{}", line));
+        }
+        engine.remove_sprite("review-move");
+        return;
+    };
+
+    match review.job.as_ref().map(|job| job.poll()) {
+        // Only a cancelled analysis comes back empty, and its review is gone by then
+        Some(Ok(Some(Some(analysis)))) => {
+            review.job = None;
+            // Start where the game was lost
+            if let Some(turning_point) = analysis.turning_point {
+                while review.ply > turning_point + 1 && review.position.undo().is_some() {
+                    review.ply -= 1;
+                }
+            }
+            review.analysis = Some(analysis);
+        }
+        Some(Err(_)) => {
+            review.job = None;
+            app.notice = Some(Notice::new("The game couldn't be analysed."));
+        }
+        _ => {}
+    }
+
+    let history = app.game.history();
+    if engine.is_action_just_pressed("move_left") && review.position.undo().is_some() {
+        review.ply -= 1;
+    }
+    if engine.is_action_just_pressed("move_right") && review.ply < history.len() {
+        review.position.play_move(&history[review.ply]).expect("recorded moves are legal");
+        review.ply += 1;
+    }
+
+    let mut lines = vec![];
+    match (&review.analysis, review.ply) {
+        (None, _) if review.job.is_some() => lines.push("Analysing the game...".to_string()),
+        (None, _) => {}
+        (Some(_), 0) => lines.push(format!("Start of the game, {} moves", history.len())),
+        (Some(analysis), ply) => {
+            let graded = &analysis.moves[ply - 1];
+            let verdict = match graded.verdict {
+                Verdict::Best => "best",
+                Verdict::Inaccuracy => "inaccuracy",
+                Verdict::Blunder => "blunder",
+            };
+            let lost_here = if analysis.turning_point == Some(ply - 1) { ", the game was lost here" } else { "" };
+            lines.push(format!("Move {}/{}: {}, {}{}", ply, history.len(), graded.mv.to_notation(), verdict, lost_here));
+
+            if graded.verdict != Verdict::Best {
+                // Both marks of a cell are listed once
+                let mut cells: Vec<BoardCoordinates> = graded.best_moves.iter().map(|mv| mv.coords).collect();
+                cells.sort_by_key(|coords| (coords.z, coords.y, coords.x));
+                cells.dedup();
+                let cells: Vec<String> = cells.iter().map(|coords| coords.to_notation()).collect();
+                let outcome = match graded.best_outcome {
+                    Outcome::Win => "win",
+                    Outcome::Draw => "draw",
+                    Outcome::Loss => "lose",
+                };
+                lines.push(format!("Would {} with {}", outcome, cells.join(", ")));
+            }
+        }
+    }
+    if let Some(analysis) = &review.analysis {
+        let summary = match (app.game.status(), analysis.turning_point) {
+            (GameStatus::Won(winner), Some(turning_point)) => {
+                format!("Player {:?} lost with move {}", winner.other(), turning_point + 1)
+            }
+            (GameStatus::Won(winner), None) => format!("Player {:?} was lost from the start", winner.other()),
+            _ => "Drawn".to_string(),
+        };
+        let estimated = if analysis.exact { "" } else { " (estimated)" };
+        lines.push(format!("{}{}", summary, estimated));
+    }
+    lines.push("Left/Right: Step through moves, R: Leave review".to_string());
+
+    let y = y_instructions(&app.game) + 7;
+    for line in 0..4 {
+        let label = format!("review-{}", line);
+        match lines.get(line) {
+            Some(text) => engine.insert_sprite(&label, text_sprite(&label, text, 0, y + line)),
+            None => engine.remove_sprite(&label),
+        }
+    }
+
+    // Brackets around the last move shown
+    match review.ply.checked_sub(1).map(|ply| history[ply].coords) {
+        Some(coords) => {
+            let frame = coords.to_frame_coordinates(&layer_offset(&app.game, coords.z));
+            engine.insert_sprite("review-move", text_sprite("review-move", "[ ]", frame.x - 1, frame.y).with_layer(0));
+        }
+        None => engine.remove_sprite("review-move"),
+    }
+}
+
+fn layer_labels_logic(engine: &mut Engine<App>, app: &mut App) {
+    let game = &app.game;
+    if game.state().depth() < 2 {
+        return;
+    }
+
+    for z in 0..game.state().depth() {
+        let str = if z == app.cursor.position().z && !game.is_game_over() {
+            format!("> Layer {} <", z + 1)
+        } else {
+            format!("  Layer {}", z + 1)
+        };
+
+        let label = format!("layer-label-{}", z);
+        let offset = layer_offset(game, z);
+        engine.insert_sprite(&label, text_sprite(&label, &str, offset.x, y_layer_labels(game)));
+    }
+}
+
+fn win_lose_logic(engine: &mut Engine<App>, app: &mut App) {
+    let game = &app.game;
+
+    let str = match game.status() {
+        GameStatus::Won(winner) => format!("Winner: {:?}", winner),
+        GameStatus::Draw => "Draw".to_string(),
+        GameStatus::InProgress => match app.side() {
+            Some(side) if !app.controllers[side].is_local() => {
+                format!("Turn: Player {:?}, {} is thinking...", game.turn(), app.controllers[side].name())
+            }
+            _ => format!("Turn: Player {:?}", game.turn()),
+        },
+    };
+
+    engine.insert_sprite("win-lose", text_sprite("win-lose", str.as_str(), 0, Y_TURN));
+}
+
+fn notice_logic(engine: &mut Engine<App>, app: &mut App) {
+    let Some(notice) = app.notice.as_mut() else {
+        engine.remove_sprite("notice");
+        return;
+    };
+
+    notice.elapsed += engine.delta;
+    if notice.elapsed >= NOTICE_TIME {
+        app.notice = None;
+        engine.remove_sprite("notice");
+        return;
+    }
+    engine.insert_sprite("notice", text_sprite("notice", &notice.text, 0, y_instructions(&app.game) + 6));
+}
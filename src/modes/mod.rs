@@ -0,0 +1,83 @@
+use vector2d::Vector2D;
+use tic_tac_toe::engine::Engine;
+use tic_tac_toe::engine::sprite::Sprite;
+use tic_tac_toe::tictactoe::player::Player;
+
+pub mod board;
+pub mod notakto;
+pub mod ultimate;
+
+pub const GRID_HEIGHT: usize = 5;
+pub const GRID_WIDTH: usize = 11;
+pub const GRID_SPACING: usize = 3;
+
+pub const Y_TURN: usize = 0;
+pub const Y_DEBUG: usize = 1;
+pub const Y_GRID: usize = 2;
+
+pub trait TextEntry {
+    // While true, keys are text and not actions
+    fn is_entering_text(&self) -> bool {
+        false
+    }
+}
+
+pub fn text_sprite(label: &str, text: &str, x: usize, y: usize) -> Sprite {
+    Sprite::from_string(label, text)
+        .with_translation(Vector2D::new(x, y))
+        .with_layer(1)
+}
+
+pub fn grid_width(columns: usize) -> usize {
+    columns * 4 - 1
+}
+
+pub fn grid_height(rows: usize) -> usize {
+    rows * 2 - 1
+}
+
+// The classic board comes from sprites/grid, other sizes are drawn the same way
+pub fn grid_sprite(label: &str, columns: usize, rows: usize, x: usize, y: usize) -> anyhow::Result<Sprite> {
+    let sprite = if (columns, rows) == (3, 3) {
+        Sprite::from_file(label, "sprites/grid")?
+    } else {
+        let cells = vec!["   "; columns].join("┃");
+        let separator = vec!["━━━"; columns].join("┃");
+        Sprite::from_text(label, &vec![cells; rows].join(&format!("\n{}\n", separator)))?
+    };
+
+    Ok(sprite
+        .with_translation(Vector2D::new(x, y))
+        .with_layer(0))
+}
+
+pub fn mark_str(mark: &Player) -> Option<&'static str> {
+    match mark {
+        Player::ONE => Some("X"),
+        Player::TWO => Some("O"),
+        Player::NONE => None,
+    }
+}
+
+pub fn base_logic<T: TextEntry>(engine: &mut Engine<T>, state: &mut T) {
+    if state.is_entering_text() {
+        return;
+    }
+
+    if engine.is_action_pressed("quit") {
+        engine.stop();
+    }
+    if engine.is_action_just_pressed("toggle_debug") {
+        engine.toggle_layer("debug");
+    }
+}
+
+pub fn fps_counter<T>(engine: &mut Engine<T>, _: &mut T) {
+    if engine.delta.as_millis() > 0.0 as u128 {
+        let fps = 1000_u128 / engine.delta.as_millis();
+        let str = format!("FPS: {} Dropped: {}", fps, engine.dropped_frames());
+
+        let sprite = text_sprite("fps", str.as_str(), 0, Y_DEBUG).on_layer("debug");
+        engine.insert_sprite("fps", sprite);
+    }
+}
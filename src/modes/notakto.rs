@@ -0,0 +1,133 @@
+use anyhow::Result;
+use crossterm::event::KeyCode;
+use vector2d::Vector2D;
+use tic_tac_toe::engine::Engine;
+use tic_tac_toe::engine::keyboard::bindings::KeyBindings;
+use tic_tac_toe::engine::sprite::Sprite;
+use tic_tac_toe::engine::window::Window;
+use tic_tac_toe::tictactoe::coordinates::BoardCoordinates;
+use tic_tac_toe::tictactoe::movement::MoveDirection;
+use tic_tac_toe::tictactoe::moves::GameStatus;
+use tic_tac_toe::tictactoe::notakto::Notakto;
+
+use crate::modes::{base_logic, fps_counter, grid_sprite, mark_str, text_sprite, TextEntry, GRID_HEIGHT, GRID_SPACING, GRID_WIDTH, Y_GRID, Y_TURN};
+
+const Y_BOARD_LABELS: usize = Y_GRID + GRID_HEIGHT;
+const Y_INSTRUCTIONS: usize = Y_BOARD_LABELS + 2;
+
+impl TextEntry for Notakto {}
+
+fn board_x(board: usize) -> usize {
+    board * (GRID_WIDTH + GRID_SPACING)
+}
+
+pub fn run(board_count: usize, key_bindings: KeyBindings) -> Result<()> {
+    let game = Notakto::new(board_count);
+    let width = board_x(game.boards().len()).max(50);
+
+    let instructions = Sprite::from_string("instructions", "Q: Quit, Space: Play, Arrows: Move, 1-9: Place")
+        .with_translation(Vector2D::new(0, Y_INSTRUCTIONS))
+        .with_layer(0);
+
+    let mut engine = Engine::new(Window::new(width, 50))
+        .set_fps(60)
+        .with_key_bindings(key_bindings)
+        .with_render_layer("debug", 10)
+        .with_sprite("instructions", instructions);
+
+    for board in 0..game.boards().len() {
+        let label = format!("grid-{}", board);
+        engine = engine.with_sprite(&label, grid_sprite(&label, 3, 3, board_x(board), Y_GRID)?);
+    }
+
+    engine
+        .with_logic(base_logic)
+        .with_logic(game_movement)
+        .with_logic(cell_selection)
+        .with_logic(cursor_logic)
+        .with_logic(markers_logic)
+        .with_logic(board_labels_logic)
+        .with_logic(win_lose_logic)
+        .with_logic(fps_counter)
+        .run(game)
+}
+
+fn game_movement(engine: &mut Engine<Notakto>, game: &mut Notakto) {
+    if engine.is_action_pressed("move_left") {
+        game.move_cursor(MoveDirection::LEFT);
+    }
+    if engine.is_action_pressed("move_right") {
+        game.move_cursor(MoveDirection::RIGHT);
+    }
+    if engine.is_action_pressed("move_up") {
+        game.move_cursor(MoveDirection::UP);
+    }
+    if engine.is_action_pressed("move_down") {
+        game.move_cursor(MoveDirection::DOWN);
+    }
+    if engine.is_action_just_pressed("place") {
+        let _ = game.play();
+    }
+}
+
+fn cell_selection(engine: &mut Engine<Notakto>, game: &mut Notakto) {
+    for event in engine.keys().pressed().to_vec() {
+        let coords = match event.code {
+            KeyCode::Char(char) => char.to_digit(10).and_then(BoardCoordinates::from_keypad),
+            _ => None,
+        };
+        if let Some(coords) = coords {
+            if game.select_cell(coords) {
+                let _ = game.play();
+            }
+        }
+    }
+}
+
+fn cursor_logic(engine: &mut Engine<Notakto>, game: &mut Notakto) {
+    if game.is_game_over() {
+        engine.remove_sprite("cursor");
+        return;
+    }
+
+    let offset = Vector2D::new(board_x(game.active_board()), Y_GRID);
+    let coords = game.cursor_position().to_frame_coordinates(&offset);
+    engine.insert_sprite("cursor", text_sprite("cursor", "x", coords.x, coords.y));
+}
+
+fn markers_logic(engine: &mut Engine<Notakto>, game: &mut Notakto) {
+    for (board, sub_game) in game.boards().iter().enumerate() {
+        let offset = Vector2D::new(board_x(board), Y_GRID);
+        for (coords, cell) in sub_game.state().cells() {
+            if let Some(marker_str) = mark_str(cell) {
+                let frame_coords = coords.to_frame_coordinates(&offset);
+                let label = format!("{}-{}-{}", board, coords.x, coords.y);
+                engine.insert_sprite(&label, text_sprite(&label, marker_str, frame_coords.x, frame_coords.y));
+            }
+        }
+    }
+}
+
+fn board_labels_logic(engine: &mut Engine<Notakto>, game: &mut Notakto) {
+    for board in 0..game.boards().len() {
+        let str = if game.is_board_dead(board) {
+            "   dead".to_string()
+        } else if board == game.active_board() && !game.is_game_over() {
+            format!(" > {} <", board + 1)
+        } else {
+            format!("   {}", board + 1)
+        };
+
+        let label = format!("board-label-{}", board);
+        engine.insert_sprite(&label, text_sprite(&label, &str, board_x(board), Y_BOARD_LABELS));
+    }
+}
+
+fn win_lose_logic(engine: &mut Engine<Notakto>, game: &mut Notakto) {
+    let str = match game.status() {
+        GameStatus::Won(winner) => format!("Winner: {:?}", winner),
+        _ => format!("Turn: Player {:?}", game.turn()),
+    };
+
+    engine.insert_sprite("win-lose", text_sprite("win-lose", str.as_str(), 0, Y_TURN));
+}
@@ -0,0 +1,177 @@
+use anyhow::Result;
+use crossterm::event::KeyCode;
+use vector2d::Vector2D;
+use tic_tac_toe::engine::Engine;
+use tic_tac_toe::engine::keyboard::bindings::KeyBindings;
+use tic_tac_toe::engine::sprite::Sprite;
+use tic_tac_toe::engine::window::Window;
+use tic_tac_toe::tictactoe::coordinates::BoardCoordinates;
+use tic_tac_toe::tictactoe::movement::MoveDirection;
+use tic_tac_toe::tictactoe::moves::GameStatus;
+use tic_tac_toe::tictactoe::player::Player;
+use tic_tac_toe::tictactoe::ultimate::UltimateTicTacToe;
+
+use crate::modes::{base_logic, fps_counter, grid_sprite, mark_str, text_sprite, TextEntry, GRID_HEIGHT, GRID_WIDTH, Y_GRID, Y_TURN};
+
+const BOARD_SPACING: usize = 2;
+const Y_INSTRUCTIONS: usize = Y_GRID + 3 * (GRID_HEIGHT + BOARD_SPACING) + 1;
+
+const X_WON: &str = " ╲       ╱ \n   ╲   ╱   \n     ╳     \n   ╱   ╲   \n ╱       ╲ ";
+const O_WON: &str = "   ╭───╮   \n  ╱     ╲  \n │       │ \n  ╲     ╱  \n   ╰───╯   ";
+
+impl TextEntry for UltimateTicTacToe {}
+
+// Boards are one column and row apart so there is room to draw a frame around them
+fn board_offset(board: BoardCoordinates) -> Vector2D<usize> {
+    Vector2D::new(
+        1 + board.x * (GRID_WIDTH + BOARD_SPACING),
+        Y_GRID + 1 + board.y * (GRID_HEIGHT + BOARD_SPACING),
+    )
+}
+
+fn all_boards() -> impl Iterator<Item = BoardCoordinates> {
+    (0..3).flat_map(|x| (0..3).map(move |y| BoardCoordinates::new(x, y)))
+}
+
+pub fn run(key_bindings: KeyBindings) -> Result<()> {
+    let instructions = Sprite::from_string("instructions", "Q: Quit, Space: Play, Arrows: Move, 1-9: Cell")
+        .with_translation(Vector2D::new(0, Y_INSTRUCTIONS))
+        .with_layer(0);
+
+    let mut engine = Engine::new(Window::new(50, 50))
+        .set_fps(60)
+        .with_key_bindings(key_bindings)
+        .with_render_layer("debug", 10)
+        .with_sprite("instructions", instructions);
+
+    for board in all_boards() {
+        let label = format!("grid-{}-{}", board.x, board.y);
+        let offset = board_offset(board);
+        engine = engine.with_sprite(&label, grid_sprite(&label, 3, 3, offset.x, offset.y)?);
+    }
+
+    engine
+        .with_logic(base_logic)
+        .with_logic(game_movement)
+        .with_logic(cell_selection)
+        .with_logic(cursor_logic)
+        .with_logic(markers_logic)
+        .with_logic(active_board_logic)
+        .with_logic(win_lose_logic)
+        .with_logic(fps_counter)
+        .run(UltimateTicTacToe::default())
+}
+
+fn game_movement(engine: &mut Engine<UltimateTicTacToe>, game: &mut UltimateTicTacToe) {
+    if engine.is_action_pressed("move_left") {
+        game.move_cursor(MoveDirection::LEFT);
+    }
+    if engine.is_action_pressed("move_right") {
+        game.move_cursor(MoveDirection::RIGHT);
+    }
+    if engine.is_action_pressed("move_up") {
+        game.move_cursor(MoveDirection::UP);
+    }
+    if engine.is_action_pressed("move_down") {
+        game.move_cursor(MoveDirection::DOWN);
+    }
+    if engine.is_action_just_pressed("place") {
+        let _ = game.play();
+    }
+}
+
+fn cell_selection(engine: &mut Engine<UltimateTicTacToe>, game: &mut UltimateTicTacToe) {
+    for event in engine.keys().pressed().to_vec() {
+        let coords = match event.code {
+            KeyCode::Char(char) => char.to_digit(10).and_then(BoardCoordinates::from_keypad),
+            _ => None,
+        };
+        if let Some(coords) = coords {
+            if game.select_cell(coords) {
+                let _ = game.play();
+            }
+        }
+    }
+}
+
+fn cursor_logic(engine: &mut Engine<UltimateTicTacToe>, game: &mut UltimateTicTacToe) {
+    if game.is_game_over() {
+        engine.remove_sprite("cursor");
+        return;
+    }
+
+    let cursor_str = if *game.turn() == Player::ONE {"x"} else {"o"};
+    let coords = game.cursor_cell().to_frame_coordinates(&board_offset(game.cursor_board()));
+    engine.insert_sprite("cursor", text_sprite("cursor", cursor_str, coords.x, coords.y));
+}
+
+fn markers_logic(engine: &mut Engine<UltimateTicTacToe>, game: &mut UltimateTicTacToe) {
+    for board in all_boards() {
+        let offset = board_offset(board);
+        let sub_game = game.board(board);
+
+        for (coords, cell) in sub_game.state().cells() {
+            if let Some(marker_str) = mark_str(cell) {
+                let frame_coords = coords.to_frame_coordinates(&offset);
+                let label = format!("{}-{}-{}-{}", board.x, board.y, coords.x, coords.y);
+                engine.insert_sprite(&label, text_sprite(&label, marker_str, frame_coords.x, frame_coords.y));
+            }
+        }
+
+        // A won board is covered by its winner's mark
+        let won_art = match sub_game.winner() {
+            Player::ONE => Some(X_WON),
+            Player::TWO => Some(O_WON),
+            Player::NONE => None,
+        };
+        if let Some(art) = won_art {
+            let label = format!("won-{}-{}", board.x, board.y);
+            if engine.get_sprite(&label).is_none() {
+                if let Ok(sprite) = Sprite::from_text(&label, art) {
+                    engine.insert_sprite(&label, sprite.with_translation(offset).with_layer(2));
+                }
+            }
+        }
+    }
+}
+
+// Frames every board the current player may play in
+fn active_board_logic(engine: &mut Engine<UltimateTicTacToe>, game: &mut UltimateTicTacToe) {
+    for board in all_boards() {
+        let labels = ["top", "bottom", "left", "right"].map(|side| format!("frame-{}-{}-{}", board.x, board.y, side));
+        if game.is_game_over() || !game.is_board_playable(board) {
+            for label in labels.iter() {
+                engine.remove_sprite(label);
+            }
+            continue;
+        }
+
+        let offset = board_offset(board);
+        let (left, top) = (offset.x - 1, offset.y - 1);
+        let horizontal = "─".repeat(GRID_WIDTH);
+        let vertical = ["│"; GRID_HEIGHT].join("\n");
+
+        let sprites = [
+            Ok(text_sprite(&labels[0], &format!("┌{}┐", horizontal), left, top)),
+            Ok(text_sprite(&labels[1], &format!("└{}┘", horizontal), left, top + GRID_HEIGHT + 1)),
+            Sprite::from_text(&labels[2], &vertical).map(|sprite| sprite.with_translation(Vector2D::new(left, offset.y))),
+            Sprite::from_text(&labels[3], &vertical).map(|sprite| sprite.with_translation(Vector2D::new(left + GRID_WIDTH + 1, offset.y))),
+        ];
+        for (label, sprite) in labels.iter().zip(sprites) {
+            if let Ok(sprite) = sprite {
+                engine.insert_sprite(label, sprite);
+            }
+        }
+    }
+}
+
+fn win_lose_logic(engine: &mut Engine<UltimateTicTacToe>, game: &mut UltimateTicTacToe) {
+    let str = match game.status() {
+        GameStatus::Won(winner) => format!("Winner: {:?}", winner),
+        GameStatus::Draw => "Draw".to_string(),
+        GameStatus::InProgress if game.active_board().is_none() => format!("Turn: Player {:?}, any board", game.turn()),
+        GameStatus::InProgress => format!("Turn: Player {:?}", game.turn()),
+    };
+
+    engine.insert_sprite("win-lose", text_sprite("win-lose", str.as_str(), 0, Y_TURN));
+}
@@ -0,0 +1,180 @@
+use std::env;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{anyhow, Error, Result};
+use tic_tac_toe::ai::computer::Difficulty;
+use tic_tac_toe::tictactoe::game::BoardSize;
+use tic_tac_toe::tictactoe::rules::Rules;
+
+const USAGE: &str = "Usage: tic-tac-toe [--mode standard|ultimate|cube] [--rules classic|misere|wild|notakto] [--boards N] [--size 3|4]
+                   [--gravity] [--width N] [--height N] [--k N] [--ai random|easy|medium|hard|mcts] [--seed N]
+                   [--bot COMMAND] [--move-time MS] [--host ADDRESS | --connect ADDRESS] [--serve] [--replay FILE]";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Opponent {
+    Computer(Difficulty),
+    // Monte Carlo tree search, for boards too big for the others
+    Mcts,
+}
+
+impl FromStr for Opponent {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_lowercase().as_str() {
+            "mcts" => Ok(Opponent::Mcts),
+            difficulty => Ok(Opponent::Computer(difficulty.parse()?)),
+        }
+    }
+}
+
+// Playing someone over the network, whoever hosts plays X
+#[derive(Debug, Clone, PartialEq)]
+pub enum Peer {
+    Host(String),
+    Connect(String),
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    #[default]
+    Standard,
+    Ultimate,
+    Cube,
+}
+
+pub struct Options {
+    pub mode: Mode,
+    pub rules: Rules,
+    // Only used by notakto
+    pub boards: usize,
+    // Edge length of the cube
+    pub size: usize,
+    pub gravity: bool,
+    // Standard board dimensions and how many in a row win, defaults depend on gravity
+    pub board: BoardSize,
+    // The computer plays O when set
+    pub ai: Option<Opponent>,
+    // Makes the computer's games reproducible
+    pub seed: Option<u64>,
+    // A program speaking the bot protocol plays O when set
+    pub bot: Option<String>,
+    // How long the bot gets for each move
+    pub move_time: Option<Duration>,
+    pub peer: Option<Peer>,
+    // Speak the bot protocol on stdin and stdout instead of showing the board
+    pub serve: bool,
+    // A recorded game to play back, one move per line
+    pub replay: Option<String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            mode: Mode::default(),
+            rules: Rules::default(),
+            boards: 1,
+            size: 3,
+            gravity: false,
+            board: BoardSize::default(),
+            ai: None,
+            seed: None,
+            bot: None,
+            move_time: None,
+            peer: None,
+            serve: false,
+            replay: None,
+        }
+    }
+}
+
+impl Options {
+    pub fn from_args() -> Result<Self> {
+        let mut options = Options::default();
+        let mut args = env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| anyhow!("Missing value for {}.\n{}", arg, USAGE));
+            match arg.as_str() {
+                "--mode" => {
+                    options.mode = match value()?.as_str() {
+                        "standard" => Mode::Standard,
+                        "ultimate" => Mode::Ultimate,
+                        "cube" => Mode::Cube,
+                        mode => return Err(anyhow!("Unknown mode '{}'.\n{}", mode, USAGE)),
+                    };
+                }
+                "--rules" => options.rules = value()?.parse()?,
+                "--boards" => {
+                    options.boards = value()?.parse()
+                        .map_err(|_| anyhow!("--boards expects a number.\n{}", USAGE))?;
+                }
+                "--size" => {
+                    options.size = match value()?.as_str() {
+                        "3" => 3,
+                        "4" => 4,
+                        size => return Err(anyhow!("Unsupported cube size '{}'.\n{}", size, USAGE)),
+                    };
+                }
+                "--gravity" => options.gravity = true,
+                "--width" => options.board.width = Some(Options::parse_size(&arg, value()?)?),
+                "--height" => options.board.height = Some(Options::parse_size(&arg, value()?)?),
+                "--k" => options.board.line_length = Some(Options::parse_size(&arg, value()?)?),
+                "--ai" => options.ai = Some(value()?.parse()?),
+                "--seed" => {
+                    options.seed = Some(value()?.parse()
+                        .map_err(|_| anyhow!("--seed expects a number.\n{}", USAGE))?);
+                }
+                "--bot" => options.bot = Some(value()?),
+                "--move-time" => {
+                    let millis = value()?.parse()
+                        .map_err(|_| anyhow!("--move-time expects a number of milliseconds.\n{}", USAGE))?;
+                    options.move_time = Some(Duration::from_millis(millis));
+                }
+                "--serve" => options.serve = true,
+                "--replay" => options.replay = Some(value()?),
+                "--host" => options.peer = Some(Peer::Host(value()?)),
+                "--connect" => options.peer = Some(Peer::Connect(value()?)),
+                "--help" | "-h" => return Err(anyhow!(USAGE)),
+                _ => return Err(anyhow!("Unknown argument '{}'.\n{}", arg, USAGE)),
+            }
+        }
+
+        if options.mode == Mode::Ultimate && options.rules != Rules::Classic {
+            return Err(anyhow!("Ultimate tic-tac-toe is only played with classic rules."));
+        }
+
+        if options.gravity && options.mode != Mode::Standard {
+            return Err(anyhow!("Gravity is only available in standard mode."));
+        }
+
+        if options.ai.is_some() && (options.mode == Mode::Ultimate || options.rules == Rules::Notakto) {
+            return Err(anyhow!("The computer doesn't play ultimate tic-tac-toe or notakto yet."));
+        }
+
+        let opponents = [options.ai.is_some(), options.bot.is_some(), options.peer.is_some(), options.replay.is_some()];
+        if opponents.iter().filter(|opponent| **opponent).count() > 1 {
+            return Err(anyhow!("Pick a single opponent: --ai, --bot, --host, --connect or --replay."));
+        }
+
+        if (options.bot.is_some() || options.peer.is_some() || options.replay.is_some())
+            && (options.mode == Mode::Ultimate || options.rules == Rules::Notakto) {
+            return Err(anyhow!("Ultimate tic-tac-toe and notakto can't be played against bots, over the network or replayed yet."));
+        }
+
+        if options.serve && (options.bot.is_some() || options.peer.is_some() || options.replay.is_some()) {
+            return Err(anyhow!("--serve only plays the computer picked with --ai."));
+        }
+
+        if options.mode == Mode::Standard {
+            options.board.check(options.gravity)?;
+        }
+
+        Ok(options)
+    }
+
+    fn parse_size(arg: &str, value: String) -> Result<usize> {
+        BoardSize::parse(arg, &value).map_err(|error| anyhow!("{}\n{}", error, USAGE))
+    }
+}
@@ -0,0 +1,302 @@
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Error, Result};
+
+use crate::ai::Ai;
+use crate::tictactoe::game::{BoardSize, TicTacToe};
+use crate::tictactoe::moves::Move;
+use crate::tictactoe::rules::Rules;
+
+// A line protocol for plugging bots in as subprocesses, in the spirit of UCI for chess.
+// The game writes commands to the bot's stdin and the bot answers on its stdout:
+//
+//   tictactoe                    answered with `name <name>`, then `ready`
+//   newgame 7 6 1 4 classic gravity
+//                                width, height, depth, how many in a row win, rules, and
+//                                `gravity` when marks fall down their column
+//   position b2 x a1 o           every move played since the start, cells and marks
+//   go 1000                      answered with `bestmove b2 x` within that many milliseconds,
+//                                or `bestmove none` once the game is over
+//   stop                         answer the last go now, every go gets exactly one bestmove
+//   quit
+//
+// Bots may also write `info <anything>`, other lines are ignored.
+
+// Everything a bot needs to set up its own board
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GameSettings {
+    pub width: usize,
+    pub height: usize,
+    pub depth: usize,
+    pub line_length: usize,
+    pub rules: Rules,
+    pub gravity: bool,
+}
+
+impl GameSettings {
+    pub fn of(game: &TicTacToe) -> Self {
+        let board = game.state();
+        Self {
+            width: board.width(),
+            height: board.height(),
+            depth: board.depth(),
+            line_length: game.line_length(),
+            rules: game.rules(),
+            gravity: game.gravity(),
+        }
+    }
+
+    // An empty board with these settings
+    pub fn new_game(&self) -> Result<TicTacToe> {
+        let (width, height, depth, line_length) = (self.width, self.height, self.depth, self.line_length);
+        if depth == 1 {
+            let board = BoardSize { width: Some(width), height: Some(height), line_length: Some(line_length) };
+            board.check(self.gravity)?;
+            return Ok(board.new_game(self.rules, self.gravity));
+        }
+        if [height, depth, line_length] != [width; 3] || self.gravity {
+            return Err(anyhow!("Only cubes won with a line all the way through have layers."));
+        }
+        Ok(TicTacToe::cube(self.rules, width))
+    }
+}
+
+impl fmt::Display for GameSettings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {} {} {}", self.width, self.height, self.depth, self.line_length, self.rules)?;
+        if self.gravity {
+            write!(f, " gravity")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for GameSettings {
+    type Err = Error;
+
+    fn from_str(settings: &str) -> Result<Self, Self::Err> {
+        let invalid = || anyhow!("'{}' should look like 7 6 1 4 classic gravity.", settings);
+        let parts: Vec<&str> = settings.split_whitespace().collect();
+        let (sizes, rest) = parts.split_at_checked(4).ok_or_else(invalid)?;
+        let sizes = sizes.iter()
+            .map(|size| size.parse().ok().filter(|size| (1..=BoardSize::MAX).contains(size)))
+            .collect::<Option<Vec<usize>>>()
+            .ok_or_else(invalid)?;
+        let (rules, gravity) = match rest {
+            [rules] => (rules.parse()?, false),
+            [rules, "gravity"] => (rules.parse()?, true),
+            _ => return Err(invalid()),
+        };
+
+        Ok(Self {
+            width: sizes[0],
+            height: sizes[1],
+            depth: sizes[2],
+            line_length: sizes[3],
+            rules,
+            gravity,
+        })
+    }
+}
+
+// What the game tells a bot
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Hello,
+    NewGame(GameSettings),
+    Position(Vec<Move>),
+    Go(Duration),
+    Stop,
+    Quit,
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Command::Hello => write!(f, "tictactoe"),
+            Command::NewGame(settings) => write!(f, "newgame {}", settings),
+            Command::Position(moves) => {
+                write!(f, "position")?;
+                for mv in moves {
+                    write!(f, " {}", mv.to_notation())?;
+                }
+                Ok(())
+            }
+            Command::Go(time) => write!(f, "go {}", time.as_millis()),
+            Command::Stop => write!(f, "stop"),
+            Command::Quit => write!(f, "quit"),
+        }
+    }
+}
+
+impl FromStr for Command {
+    type Err = Error;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let line = line.trim();
+        let (keyword, args) = line.split_once(' ').unwrap_or((line, ""));
+        match keyword {
+            "tictactoe" => Ok(Command::Hello),
+            "newgame" => Ok(Command::NewGame(args.parse()?)),
+            "position" => {
+                let parts: Vec<&str> = args.split_whitespace().collect();
+                let moves = parts.chunks(2)
+                    .map(|mv| mv.join(" ").parse())
+                    .collect::<Result<Vec<Move>>>()?;
+                Ok(Command::Position(moves))
+            }
+            "go" => {
+                let millis = args.trim().parse()
+                    .map_err(|_| anyhow!("go expects a time in milliseconds."))?;
+                Ok(Command::Go(Duration::from_millis(millis)))
+            }
+            "stop" => Ok(Command::Stop),
+            "quit" => Ok(Command::Quit),
+            _ => Err(anyhow!("Unknown command '{}'.", keyword)),
+        }
+    }
+}
+
+// What a bot answers
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reply {
+    Name(String),
+    Ready,
+    BestMove(Option<Move>),
+    Info(String),
+}
+
+impl fmt::Display for Reply {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Reply::Name(name) => write!(f, "name {}", name),
+            Reply::Ready => write!(f, "ready"),
+            Reply::BestMove(Some(mv)) => write!(f, "bestmove {}", mv.to_notation()),
+            Reply::BestMove(None) => write!(f, "bestmove none"),
+            Reply::Info(info) => write!(f, "info {}", info),
+        }
+    }
+}
+
+impl Reply {
+    // None for lines that aren't replies
+    pub fn parse(line: &str) -> Option<Result<Self>> {
+        let line = line.trim();
+        let (keyword, args) = line.split_once(' ').unwrap_or((line, ""));
+        let reply = match keyword {
+            "name" => Ok(Reply::Name(args.trim().to_string())),
+            "ready" => Ok(Reply::Ready),
+            "bestmove" if args.trim() == "none" => Ok(Reply::BestMove(None)),
+            "bestmove" => args.parse().map(|mv| Reply::BestMove(Some(mv))),
+            "info" => Ok(Reply::Info(args.trim().to_string())),
+            _ => return None,
+        };
+        Some(reply)
+    }
+}
+
+// Speaks the protocol as a bot until told to quit or the input ends. Commands that can't be
+// followed are answered with an info line and otherwise ignored.
+pub fn serve(ai: &mut dyn Ai, input: impl BufRead, mut output: impl Write) -> Result<()> {
+    let mut settings = GameSettings::of(&TicTacToe::default());
+    let mut game = settings.new_game()?;
+
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let result = line.parse().and_then(|command| match command {
+            Command::Hello => {
+                writeln!(output, "{}", Reply::Name(ai.name()))?;
+                writeln!(output, "{}", Reply::Ready)?;
+                Ok(true)
+            }
+            Command::NewGame(new_settings) => {
+                game = new_settings.new_game()?;
+                settings = new_settings;
+                Ok(true)
+            }
+            Command::Position(moves) => {
+                let mut position = settings.new_game()?;
+                for mv in &moves {
+                    position.play_move(mv)?;
+                }
+                game = position;
+                Ok(true)
+            }
+            Command::Go(time) => {
+                let start = Instant::now();
+                let mv = ai.choose_move_until(&game, &|| start.elapsed() >= time);
+                writeln!(output, "{}", Reply::BestMove(mv))?;
+                Ok(true)
+            }
+            // Moves are chosen before the next command is read, so there is nothing to stop
+            Command::Stop => Ok(true),
+            Command::Quit => Ok(false),
+        });
+
+        match result {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(error) => writeln!(output, "{}", Reply::Info(error.to_string()))?,
+        }
+        output.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::computer::{ComputerPlayer, Difficulty};
+
+    #[test]
+    fn commands_round_trip() {
+        let settings = GameSettings { width: 7, height: 6, depth: 1, line_length: 4, rules: Rules::Misere, gravity: true };
+        let commands = [
+            Command::Hello,
+            Command::NewGame(settings),
+            Command::Position(vec!["b2 x".parse().unwrap(), "a1.2 o".parse().unwrap()]),
+            Command::Go(Duration::from_millis(250)),
+            Command::Stop,
+            Command::Quit,
+        ];
+        for command in commands {
+            assert_eq!(command.to_string().parse::<Command>().unwrap(), command);
+        }
+    }
+
+    #[test]
+    fn settings_are_checked() {
+        assert!("7 6 1 4 classic gravity".parse::<GameSettings>().is_ok());
+        for settings in ["7 6 1 classic", "7 6 1 0 classic", "27 6 1 4 classic", "7 6 1 4 gomoku", "7 6 1 4 classic sideways"] {
+            assert!(settings.parse::<GameSettings>().is_err(), "{}", settings);
+        }
+        assert!("3 3 3 3 classic gravity".parse::<GameSettings>().unwrap().new_game().is_err());
+        assert!("3 3 1 5 classic".parse::<GameSettings>().unwrap().new_game().is_err());
+    }
+
+    #[test]
+    fn replies_skip_unknown_lines() {
+        assert_eq!(Reply::parse("bestmove b2 x").unwrap().unwrap(), Reply::BestMove(Some("b2 x".parse().unwrap())));
+        assert_eq!(Reply::parse("bestmove none").unwrap().unwrap(), Reply::BestMove(None));
+        assert!(Reply::parse("bestmove b2").unwrap().is_err());
+        assert!(Reply::parse("thinking hard").is_none());
+    }
+
+    #[test]
+    fn serve_answers_every_go() {
+        let input = "tictactoe\nnewgame 3 3 1 3 classic\nposition a1 x b1 o a2 x b2 o\ngo 1000\nposition a9 x\nquit\ngo 1000\n";
+        let mut output = vec![];
+        serve(&mut ComputerPlayer::new(Difficulty::Hard), input.as_bytes(), &mut output).unwrap();
+        let lines: Vec<&str> = std::str::from_utf8(&output).unwrap().lines().collect();
+        assert_eq!(lines[..3], ["name Hard computer", "ready", "bestmove a3 x"]);
+        assert!(lines[3].starts_with("info "));
+        assert_eq!(lines.len(), 4);
+    }
+}
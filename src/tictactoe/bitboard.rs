@@ -0,0 +1,253 @@
+use std::sync::Arc;
+
+use crate::tictactoe::board::{BoardState, Line};
+use crate::tictactoe::coordinates::BoardCoordinates;
+use crate::tictactoe::game::TicTacToe;
+use crate::tictactoe::moves::GameStatus;
+use crate::tictactoe::player::Player;
+use crate::tictactoe::rules::Rules;
+
+pub const MAX_CELLS: usize = 64;
+
+// A position as one bitmask per mark. Bit i is cell i in BoardState order.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Bitboard {
+    x: u64,
+    o: u64,
+    turn: Player,
+}
+
+impl Bitboard {
+    pub fn empty() -> Self {
+        Self { x: 0, o: 0, turn: Player::ONE }
+    }
+
+    pub fn from_marks(x: u64, o: u64, turn: Player) -> Self {
+        Self { x, o, turn }
+    }
+
+    // Cells holding `mark`, X for Player::ONE and O for Player::TWO
+    pub fn marks(&self, mark: &Player) -> u64 {
+        match mark {
+            Player::ONE => self.x,
+            Player::TWO => self.o,
+            Player::NONE => 0,
+        }
+    }
+
+    pub fn occupied(&self) -> u64 {
+        self.x | self.o
+    }
+
+    pub fn turn(&self) -> &Player {
+        &self.turn
+    }
+}
+
+// Everything about a game that stays the same from move to move, with the winning
+// lines precomputed as masks. Only boards of up to 64 cells fit.
+#[derive(Clone, Debug)]
+pub struct BitboardLayout {
+    rules: Rules,
+    gravity: bool,
+    state: BoardState,
+    line_length: usize,
+    lines: Arc<Vec<Line>>,
+    line_masks: Vec<u64>,
+    column_masks: Vec<u64>,
+    full: u64,
+}
+
+impl BitboardLayout {
+    pub fn new(game: &TicTacToe) -> Option<Self> {
+        let board = game.state();
+        let cells = board.width() * board.height() * board.depth();
+        if cells > MAX_CELLS {
+            return None;
+        }
+
+        let empty = BoardState::new(board.width(), board.height(), board.depth());
+        let line_masks = game.lines().iter()
+            .map(|line| line.iter().fold(0, |mask, coords| mask | 1 << empty.index(coords)))
+            .collect();
+        // Cells of a column are next to each other, the bottom one having the highest bit
+        let column_masks = (0..board.width() * board.depth())
+            .map(|column| (u64::MAX >> (MAX_CELLS - board.height())) << (column * board.height()))
+            .collect();
+
+        Some(Self {
+            rules: game.rules(),
+            gravity: game.gravity(),
+            line_length: game.line_length(),
+            lines: game.shared_lines(),
+            line_masks,
+            column_masks,
+            full: if cells == MAX_CELLS { u64::MAX } else { (1 << cells) - 1 },
+            state: empty,
+        })
+    }
+
+    // Whether positions of `game` can be encoded with this layout
+    pub fn matches(&self, game: &TicTacToe) -> bool {
+        self.rules == game.rules()
+            && self.gravity == game.gravity()
+            && self.state.width() == game.state().width()
+            && self.state.height() == game.state().height()
+            && self.state.depth() == game.state().depth()
+            && *self.lines == game.lines()
+    }
+
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    pub fn gravity(&self) -> bool {
+        self.gravity
+    }
+
+    // The empty board, for its dimensions and cell order
+    pub fn board(&self) -> &BoardState {
+        &self.state
+    }
+
+    pub fn line_masks(&self) -> &[u64] {
+        &self.line_masks
+    }
+
+    pub fn cell_count(&self) -> usize {
+        self.full.count_ones() as usize
+    }
+
+    pub fn index(&self, coords: &BoardCoordinates) -> usize {
+        self.state.index(coords)
+    }
+
+    pub fn coordinates(&self, index: usize) -> BoardCoordinates {
+        self.state.coordinates(index)
+    }
+
+    pub fn encode(&self, game: &TicTacToe) -> Bitboard {
+        let mut bitboard = Bitboard { x: 0, o: 0, turn: game.turn().clone() };
+        for (coords, cell) in game.state().cells() {
+            let bit = 1 << self.index(&coords);
+            match cell {
+                Player::ONE => bitboard.x |= bit,
+                Player::TWO => bitboard.o |= bit,
+                Player::NONE => {}
+            }
+        }
+        bitboard
+    }
+
+    pub fn decode(&self, bitboard: &Bitboard) -> TicTacToe {
+        let mut state = self.state.clone();
+        for index in bits(bitboard.x) {
+            state.set(&self.coordinates(index), Player::ONE);
+        }
+        for index in bits(bitboard.o) {
+            state.set(&self.coordinates(index), Player::TWO);
+        }
+        TicTacToe::from_position(self.rules, state, self.line_length, self.lines.clone(), self.gravity, bitboard.turn.clone())
+    }
+
+    // Cells the player to move may fill, none once the game is over
+    pub fn moves(&self, bitboard: &Bitboard) -> u64 {
+        if self.is_game_over(bitboard) {
+            return 0;
+        }
+
+        let empty = !bitboard.occupied() & self.full;
+        if !self.gravity {
+            return empty;
+        }
+        self.column_masks.iter()
+            .map(|column| empty & column)
+            .filter(|column| *column != 0)
+            .fold(0, |moves, column| moves | 1 << (63 - column.leading_zeros()))
+    }
+
+    // Places the current player's mark, `chosen` only matters when players pick their mark
+    pub fn play(&self, bitboard: &Bitboard, index: usize, chosen: &Player) -> Bitboard {
+        let mut next = bitboard.clone();
+        match self.rules.mark(&bitboard.turn, chosen) {
+            Player::ONE => next.x |= 1 << index,
+            _ => next.o |= 1 << index,
+        }
+        next.turn = bitboard.turn.other();
+        next
+    }
+
+    pub fn has_line(&self, marks: u64) -> bool {
+        self.line_masks.iter().any(|line| marks & line == *line)
+    }
+
+    // Lines only ever appear on the last move, so they belong to whoever just played
+    pub fn winner(&self, bitboard: &Bitboard) -> Player {
+        if self.has_line(bitboard.x) || self.has_line(bitboard.o) {
+            self.rules.winner_of_line(&bitboard.turn.other())
+        } else {
+            Player::NONE
+        }
+    }
+
+    pub fn status(&self, bitboard: &Bitboard) -> GameStatus {
+        match self.winner(bitboard) {
+            Player::NONE if bitboard.occupied() == self.full => GameStatus::Draw,
+            Player::NONE => GameStatus::InProgress,
+            winner => GameStatus::Won(winner),
+        }
+    }
+
+    pub fn is_game_over(&self, bitboard: &Bitboard) -> bool {
+        bitboard.occupied() == self.full || self.winner(bitboard) != Player::NONE
+    }
+}
+
+// Indices of the set bits of a mask, lowest first
+pub fn bits(mut mask: u64) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        if mask == 0 {
+            return None;
+        }
+        let index = mask.trailing_zeros() as usize;
+        mask &= mask - 1;
+        Some(index)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tictactoe::game::{played, played_on};
+
+    #[test]
+    fn positions_survive_encoding() {
+        let gravity = TicTacToe::sized(Rules::Classic, 4, 4, 3).with_gravity(true);
+        let games = [
+            played(Rules::Classic, &["a1", "b1", "a2", "b2", "a3"]),
+            played(Rules::Wild, &["b2 o", "a1 o", "c3 x"]),
+            played_on(gravity, &["a4", "b4", "a3"]),
+        ];
+        for game in games {
+            let layout = BitboardLayout::new(&game).unwrap();
+            let decoded = layout.decode(&layout.encode(&game));
+            assert_eq!(decoded.state(), game.state());
+            assert_eq!(decoded.turn(), game.turn());
+            assert_eq!(decoded.status(), game.status());
+        }
+    }
+
+    #[test]
+    fn gravity_only_fills_the_bottom_of_each_column() {
+        let game = played_on(TicTacToe::sized(Rules::Classic, 4, 4, 3).with_gravity(true), &["b4", "b3"]);
+        let layout = BitboardLayout::new(&game).unwrap();
+        let mut cells: Vec<BoardCoordinates> = bits(layout.moves(&layout.encode(&game)))
+            .map(|index| layout.coordinates(index))
+            .collect();
+        let mut bottoms: Vec<BoardCoordinates> = game.legal_moves().into_iter().map(|mv| mv.coords).collect();
+        cells.sort_by_key(|coords| coords.x);
+        bottoms.sort_by_key(|coords| coords.x);
+        assert_eq!(cells.iter().map(|coords| coords.to_notation()).collect::<Vec<_>>(), ["a4", "b2", "c4", "d4"]);
+        assert_eq!(cells, bottoms);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::tictactoe::coordinates::BoardCoordinates;
+use crate::tictactoe::player::Player;
+use crate::tictactoe::symmetry::Symmetry;
+
+pub type Line = Vec<BoardCoordinates>;
+
+// Cells of a width x height board, stacked `depth` layers high for 3D games
+#[derive(Clone, PartialEq, Debug)]
+pub struct BoardState {
+    width: usize,
+    height: usize,
+    depth: usize,
+    cells: Vec<Player>,
+    // Number of cells holding a mark
+    filled: usize,
+}
+
+impl BoardState {
+    pub fn new(width: usize, height: usize, depth: usize) -> Self {
+        Self {
+            width,
+            height,
+            depth,
+            cells: vec![Player::NONE; width * height * depth],
+            filled: 0,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn contains(&self, coords: &BoardCoordinates) -> bool {
+        coords.x < self.width && coords.y < self.height && coords.z < self.depth
+    }
+
+    pub fn get(&self, coords: &BoardCoordinates) -> &Player {
+        &self.cells[self.index(coords)]
+    }
+
+    pub fn set(&mut self, coords: &BoardCoordinates, player: Player) {
+        let index = self.index(coords);
+        match (&self.cells[index], &player) {
+            (Player::NONE, Player::NONE) => {}
+            (Player::NONE, _) => self.filled += 1,
+            (_, Player::NONE) => self.filled -= 1,
+            _ => {}
+        }
+        self.cells[index] = player;
+    }
+
+    pub fn is_empty_at(&self, coords: &BoardCoordinates) -> bool {
+        self.contains(coords) && *self.get(coords) == Player::NONE
+    }
+
+    // Layer by layer, column by column
+    pub fn cells(&self) -> impl Iterator<Item = (BoardCoordinates, &Player)> {
+        self.cells.iter().enumerate().map(|(index, cell)| (self.coordinates(index), cell))
+    }
+
+    pub fn is_filled(&self) -> bool {
+        self.filled == self.cells.len()
+    }
+
+    // Rotations and reflections that keep the shape of the board
+    pub fn symmetries(&self) -> Vec<Symmetry> {
+        Symmetry::ALL.into_iter().filter(|symmetry| symmetry.fits(self.width, self.height)).collect()
+    }
+
+    pub fn transform(&self, symmetry: Symmetry) -> BoardState {
+        let mut transformed = self.clone();
+        for (coords, cell) in self.cells() {
+            transformed.set(&coords.transform(symmetry, self.width, self.height), cell.clone());
+        }
+        transformed
+    }
+
+    // The same representative for every rotation and reflection of a position, along with the
+    // symmetry that turns this board into it. Its inverse maps cells of the canonical board back here.
+    pub fn canonical(&self) -> (BoardState, Symmetry) {
+        self.symmetries().into_iter()
+            .map(|symmetry| (self.transform(symmetry), symmetry))
+            .min_by(|(a, _), (b, _)| a.cells.cmp(&b.cells))
+            .expect("the identity always fits")
+    }
+
+    // Every straight line of `length` cells, through layers included
+    pub fn winning_lines(&self, length: usize) -> Vec<Line> {
+        let mut directions = vec![];
+        for dz in -1..=1 {
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    // Only one of each pair of opposite directions
+                    let first = [dz, dx, dy].into_iter().find(|d| *d != 0);
+                    if first == Some(1) {
+                        directions.push((dx, dy, dz));
+                    }
+                }
+            }
+        }
+
+        let mut lines = vec![];
+        for index in 0..self.cells.len() {
+            let start = self.coordinates(index);
+            for (dx, dy, dz) in directions.iter() {
+                let line: Option<Line> = (0..length as isize)
+                    .map(|step| {
+                        let x = start.x as isize + dx * step;
+                        let y = start.y as isize + dy * step;
+                        let z = start.z as isize + dz * step;
+                        if x < 0 || y < 0 || z < 0 {
+                            return None;
+                        }
+                        let coords = BoardCoordinates::new(x as usize, y as usize).with_z(z as usize);
+                        self.contains(&coords).then_some(coords)
+                    })
+                    .collect();
+                if let Some(line) = line {
+                    lines.push(line);
+                }
+            }
+        }
+        lines
+    }
+
+    // Position of a cell in the flat cell list, layer by layer and column by column
+    pub fn index(&self, coords: &BoardCoordinates) -> usize {
+        (coords.z * self.width + coords.x) * self.height + coords.y
+    }
+
+    pub fn coordinates(&self, index: usize) -> BoardCoordinates {
+        let y = index % self.height;
+        let x = (index / self.height) % self.width;
+        let z = index / (self.height * self.width);
+        BoardCoordinates::new(x, y).with_z(z)
+    }
+}
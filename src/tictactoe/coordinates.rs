@@ -1,9 +1,13 @@
 use vector2d::Vector2D;
 
-#[derive(Default, Clone, Copy)]
+use crate::tictactoe::symmetry::Symmetry;
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
 pub struct BoardCoordinates {
     pub x: usize,
     pub y: usize,
+    // Layer, always 0 on flat boards
+    pub z: usize,
 }
 
 #[derive(Default, Clone, Copy)]
@@ -16,8 +20,63 @@ impl BoardCoordinates {
     pub fn new(x: usize, y: usize) -> Self {
         Self {
             x,
-            y
+            y,
+            z: 0
+        }
+    }
+
+    pub fn with_z(mut self, z: usize) -> Self {
+        self.z = z;
+        self
+    }
+
+    // Laid out like a numeric keypad: 7 8 9 is the top row and 1 2 3 the bottom one
+    pub fn from_keypad(digit: u32) -> Option<Self> {
+        if !(1..=9).contains(&digit) {
+            return None;
         }
+        let index = digit as usize - 1;
+        Some(BoardCoordinates::new(index % 3, 2 - index / 3))
+    }
+
+    // Column letter then row number counted from the top, e.g. "b2" is the center of a 3x3 board.
+    // Layers above the first are added after a dot, "b2.3" is the center of the top of a cube.
+    pub fn from_notation(notation: &str) -> Option<Self> {
+        let notation = notation.trim().to_ascii_lowercase();
+        let (cell, layer) = notation.split_once('.').unwrap_or((&notation, "1"));
+        let mut chars = cell.chars();
+        let column = chars.next().filter(|char| char.is_ascii_lowercase())?;
+        let row: usize = chars.as_str().parse().ok()?;
+        let layer: usize = layer.parse().ok()?;
+        if row == 0 || layer == 0 {
+            return None;
+        }
+        Some(BoardCoordinates::new((column as u8 - b'a') as usize, row - 1).with_z(layer - 1))
+    }
+
+    pub fn to_notation(&self) -> String {
+        let cell = format!("{}{}", (b'a' + self.x as u8) as char, self.y + 1);
+        if self.z == 0 {
+            cell
+        } else {
+            format!("{}.{}", cell, self.z + 1)
+        }
+    }
+
+    // Where the cell ends up once a width x height board is rotated or reflected, layers stay put
+    pub fn transform(&self, symmetry: Symmetry, width: usize, height: usize) -> Self {
+        let (x, y) = (self.x, self.y);
+        let (x, y) = match symmetry {
+            Symmetry::Identity => (x, y),
+            Symmetry::Rotate90 => (height - 1 - y, x),
+            Symmetry::Rotate180 => (width - 1 - x, height - 1 - y),
+            Symmetry::Rotate270 => (y, width - 1 - x),
+            Symmetry::FlipHorizontal => (width - 1 - x, y),
+            Symmetry::FlipVertical => (x, height - 1 - y),
+            Symmetry::FlipDiagonal => (y, x),
+            Symmetry::FlipAntiDiagonal => (height - 1 - y, width - 1 - x),
+        };
+        BoardCoordinates { x, y, z: self.z }
     }
 
     pub fn to_frame_coordinates(&self, board_offset: &Vector2D<usize>) -> FrameCoordinates {
@@ -26,4 +85,41 @@ impl BoardCoordinates {
             y: self.y * 2 + board_offset.y
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notation_is_column_then_row_from_the_top() {
+        assert_eq!(BoardCoordinates::from_notation("a1"), Some(BoardCoordinates::new(0, 0)));
+        assert_eq!(BoardCoordinates::from_notation(" B2 "), Some(BoardCoordinates::new(1, 1)));
+        assert_eq!(BoardCoordinates::from_notation("c12"), Some(BoardCoordinates::new(2, 11)));
+        assert_eq!(BoardCoordinates::new(4, 9).to_notation(), "e10");
+    }
+
+    #[test]
+    fn notation_rejects_anything_else() {
+        for notation in ["", "b", "2b", "b0", "b-1", "bb", "?2"] {
+            assert_eq!(BoardCoordinates::from_notation(notation), None, "{}", notation);
+        }
+    }
+
+    #[test]
+    fn layers_come_after_a_dot() {
+        assert_eq!(BoardCoordinates::from_notation("b2.3"), Some(BoardCoordinates::new(1, 1).with_z(2)));
+        assert_eq!(BoardCoordinates::new(1, 1).with_z(2).to_notation(), "b2.3");
+        assert_eq!(BoardCoordinates::new(1, 1).to_notation(), "b2");
+        for notation in ["b2.0", "b2.", "b2.x", ".3"] {
+            assert_eq!(BoardCoordinates::from_notation(notation), None, "{}", notation);
+        }
+    }
+
+    #[test]
+    fn keypad_digits_follow_the_keypad_layout() {
+        assert_eq!(BoardCoordinates::from_keypad(7), Some(BoardCoordinates::new(0, 0)));
+        assert_eq!(BoardCoordinates::from_keypad(5), Some(BoardCoordinates::new(1, 1)));
+        assert_eq!(BoardCoordinates::from_keypad(3), Some(BoardCoordinates::new(2, 2)));
+        assert_eq!(BoardCoordinates::from_keypad(0), None);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::tictactoe::coordinates::BoardCoordinates;
+use crate::tictactoe::game::TicTacToe;
+use crate::tictactoe::movement::MoveDirection;
+use crate::tictactoe::moves::{GameStatus, MoveError};
+use crate::tictactoe::player::Player;
+
+// The cell a player is about to play. It lives outside of TicTacToe so the rules
+// can be used by the AI and over the network without a cursor.
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub struct Cursor {
+    position: BoardCoordinates,
+}
+
+impl Cursor {
+    pub fn position(&self) -> BoardCoordinates {
+        self.position
+    }
+
+    pub fn move_towards(&mut self, game: &TicTacToe, direction: MoveDirection) {
+        if game.gravity() {
+            self.move_column(game, direction);
+            return;
+        }
+
+        if let Some(new_pos) = Cursor::find_next_available_cell(game, &self.position, &direction) {
+            self.position = new_pos;
+            return;
+        }
+
+        // If no empty cell found in the desired direction, move diagonally
+        let pos = self.position;
+        let state = game.state();
+        let candidates: Vec<BoardCoordinates> = match direction {
+            MoveDirection::UP | MoveDirection::DOWN => {
+                (0..state.width()).map(|x| BoardCoordinates::new(x, pos.y).with_z(pos.z)).collect()
+            }
+            MoveDirection::LEFT | MoveDirection::RIGHT => {
+                (0..state.height()).map(|y| BoardCoordinates::new(pos.x, y).with_z(pos.z)).collect()
+            }
+            MoveDirection::ABOVE | MoveDirection::BELOW => {
+                state.cells()
+                    .filter(|(coords, _)| coords.z == pos.z)
+                    .map(|(coords, _)| coords)
+                    .collect()
+            }
+        };
+
+        for new_pos in candidates {
+            if let Some(new_pos) = Cursor::find_next_available_cell(game, &new_pos, &direction) {
+                self.position = new_pos;
+                return;
+            }
+        }
+    }
+
+    // With gravity the cursor only picks a column
+    fn move_column(&mut self, game: &TicTacToe, direction: MoveDirection) {
+        let columns: Vec<usize> = match direction {
+            MoveDirection::LEFT => (0..self.position.x).rev().collect(),
+            MoveDirection::RIGHT => (self.position.x + 1..game.state().width()).collect(),
+            _ => vec![],
+        };
+
+        if let Some(x) = columns.into_iter().find(|x| game.drop_target(*x, self.position.z).is_some()) {
+            self.position = BoardCoordinates::new(x, 0).with_z(self.position.z);
+        }
+    }
+
+    // Moves the cursor straight to a cell, as long as it is on the board and empty.
+    // With gravity only the column matters.
+    pub fn select(&mut self, game: &TicTacToe, coords: BoardCoordinates) -> bool {
+        if game.gravity() {
+            if game.drop_target(coords.x, coords.z).is_none() {
+                return false;
+            }
+            self.position = BoardCoordinates { y: 0, ..coords };
+            return true;
+        }
+
+        if !game.state().is_empty_at(&coords) {
+            return false;
+        }
+        self.position = coords;
+        true
+    }
+
+    // The cell playing would fill
+    pub fn target(&self, game: &TicTacToe) -> Option<BoardCoordinates> {
+        if game.gravity() {
+            game.drop_target(self.position.x, self.position.z)
+        } else {
+            game.state().is_empty_at(&self.position).then_some(self.position)
+        }
+    }
+
+    // Plays the cell under the cursor, or the bottom of its column with gravity
+    pub fn play(&mut self, game: &mut TicTacToe) -> Result<GameStatus, MoveError> {
+        let status = game.place(self.target(game).unwrap_or(self.position))?;
+        self.follow(game);
+        Ok(status)
+    }
+
+    // Moves off a cell or column that can no longer be played
+    pub fn follow(&mut self, game: &TicTacToe) {
+        if game.gravity() {
+            if self.target(game).is_none() {
+                self.move_column(game, MoveDirection::RIGHT);
+            }
+            if self.target(game).is_none() {
+                self.move_column(game, MoveDirection::LEFT);
+            }
+        } else if !game.state().is_empty_at(&self.position) {
+            if let Some(empty_cell) = Cursor::find_empty_cell(game) {
+                self.position = empty_cell;
+            }
+        }
+    }
+
+    fn find_empty_cell(game: &TicTacToe) -> Option<BoardCoordinates> {
+        game.state().cells()
+            .find(|(_, cell)| **cell == Player::NONE)
+            .map(|(coords, _)| coords)
+    }
+
+    // Nearest empty cell from `pos` going in `direction`
+    fn find_next_available_cell(game: &TicTacToe, pos: &BoardCoordinates, direction: &MoveDirection) -> Option<BoardCoordinates> {
+        let mut next = Cursor::step(game, pos, direction);
+        while let Some(coords) = next {
+            if game.state().is_empty_at(&coords) {
+                return Some(coords);
+            }
+            next = Cursor::step(game, &coords, direction);
+        }
+        None
+    }
+
+    fn step(game: &TicTacToe, pos: &BoardCoordinates, direction: &MoveDirection) -> Option<BoardCoordinates> {
+        let coords = match direction {
+            MoveDirection::UP => BoardCoordinates { y: pos.y.checked_sub(1)?, ..*pos },
+            MoveDirection::DOWN => BoardCoordinates { y: pos.y + 1, ..*pos },
+            MoveDirection::LEFT => BoardCoordinates { x: pos.x.checked_sub(1)?, ..*pos },
+            MoveDirection::RIGHT => BoardCoordinates { x: pos.x + 1, ..*pos },
+            MoveDirection::BELOW => BoardCoordinates { z: pos.z.checked_sub(1)?, ..*pos },
+            MoveDirection::ABOVE => BoardCoordinates { z: pos.z + 1, ..*pos },
+        };
+        game.state().contains(&coords).then_some(coords)
+    }
+}
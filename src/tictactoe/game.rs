@@ -1,181 +1,446 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+
+use crate::tictactoe::board::{BoardState, Line};
 use crate::tictactoe::coordinates::BoardCoordinates;
-use crate::tictactoe::movement::MoveDirection;
+use crate::tictactoe::moves::{GameStatus, Move, MoveError};
 use crate::tictactoe::player::Player;
-
-type BoardState = Vec<Vec<Player>>;
+use crate::tictactoe::rules::Rules;
 
 const BOARD_SIZE: usize = 3;
 
+// Width, height and how many in a row win, as picked on the command line. Those left out
+// follow `TicTacToe::default_size`.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct BoardSize {
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub line_length: Option<usize>,
+}
+
+impl BoardSize {
+    // Every column needs a letter
+    pub const MAX: usize = 26;
+
+    // A size given for `arg`, like --width
+    pub fn parse(arg: &str, value: &str) -> Result<usize> {
+        match value.parse() {
+            Ok(size) if (1..=BoardSize::MAX).contains(&size) => Ok(size),
+            _ => Err(anyhow!("{} expects a number between 1 and {}.", arg, BoardSize::MAX)),
+        }
+    }
+
+    // Width, height and line length with the defaults filled in
+    pub fn resolve(&self, gravity: bool) -> (usize, usize, usize) {
+        let (width, height, line_length) = TicTacToe::default_size(gravity);
+        (
+            self.width.unwrap_or(width),
+            self.height.unwrap_or(height),
+            self.line_length.unwrap_or(line_length),
+        )
+    }
+
+    // No line would fit on the board when it is longer than both sides
+    pub fn check(&self, gravity: bool) -> Result<()> {
+        let (width, height, line_length) = self.resolve(gravity);
+        if line_length > width.max(height) {
+            return Err(anyhow!("{} in a row can't fit on a {}x{} board.", line_length, width, height));
+        }
+        Ok(())
+    }
+
+    pub fn new_game(&self, rules: Rules, gravity: bool) -> TicTacToe {
+        let (width, height, line_length) = self.resolve(gravity);
+        TicTacToe::sized(rules, width, height, line_length).with_gravity(gravity)
+    }
+}
+
+// The rules of a single board. Only `place` and `toggle_mark` change it, where the
+// player is about to play is tracked by a Cursor.
+#[derive(Clone)]
 pub struct TicTacToe {
-    pub turn: Player,
-    pub winner: Player,
-    pub state: BoardState,
-    pub cursor_position: BoardCoordinates,
+    rules: Rules,
+    turn: Player,
+    // The mark the current player places when the rules let them choose
+    mark: Player,
+    winner: Player,
+    state: BoardState,
+    // Marks fall to the lowest empty cell of their column
+    gravity: bool,
+    // How many in a row win
+    line_length: usize,
+    // Shared between clones, search copies games a lot
+    lines: Arc<Vec<Line>>,
+    // Indices of the lines going through each cell
+    cell_lines: Arc<Vec<Vec<usize>>>,
+    // Moves played so far, oldest first
+    history: Vec<Move>,
 }
 
 impl Default for TicTacToe {
     fn default() -> Self {
-        let mut state = Vec::with_capacity(3);
-        for _ in 0..BOARD_SIZE {
-            let col = vec![Player::NONE; BOARD_SIZE];
-            state.push(col)
-        }
+        TicTacToe::new(Rules::default())
+    }
+}
+
+impl TicTacToe {
+    pub fn new(rules: Rules) -> Self {
+        TicTacToe::from_board(rules, BoardState::new(BOARD_SIZE, BOARD_SIZE, 1), BOARD_SIZE)
+    }
 
+    // A width x height board won with `line_length` in a row, which has to fit on the board
+    pub fn sized(rules: Rules, width: usize, height: usize, line_length: usize) -> Self {
+        TicTacToe::from_board(rules, BoardState::new(width, height, 1), line_length)
+    }
+
+    // A size x size x size cube, won with a line running all the way through it
+    pub fn cube(rules: Rules, size: usize) -> Self {
+        TicTacToe::from_board(rules, BoardState::new(size, size, size), size)
+    }
+
+    // Width, height and line length when none are given: Connect Four with gravity, the classic board otherwise
+    pub fn default_size(gravity: bool) -> (usize, usize, usize) {
+        if gravity { (7, 6, 4) } else { (BOARD_SIZE, BOARD_SIZE, BOARD_SIZE) }
+    }
+
+    pub fn with_gravity(mut self, gravity: bool) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    fn from_board(rules: Rules, state: BoardState, line_length: usize) -> Self {
+        // Every position would hold a line of no marks
+        assert!(line_length > 0, "Lines need at least one cell.");
+        assert!(line_length <= state.width().max(state.height()).max(state.depth()), "Lines can't be longer than the board.");
+        let lines = Arc::new(state.winning_lines(line_length));
         Self {
+            rules,
+            line_length,
             turn: Player::ONE,
+            mark: Player::ONE,
             winner: Player::NONE,
+            cell_lines: Arc::new(TicTacToe::index_lines(&state, &lines)),
+            lines,
             state,
-            cursor_position: BoardCoordinates::new(0, 0),
+            gravity: false,
+            history: vec![],
         }
     }
-}
 
-impl TicTacToe {
-    pub fn move_cursor(&mut self, direction: MoveDirection) {
-        if let Some(new_pos) = self.find_next_available_cell(&self.cursor_position, &direction) {
-            self.cursor_position = new_pos;
-            return;
-        }
-
-        // If no empty cell found in the desired direction, move diagonally
-        match direction {
-            MoveDirection::UP | MoveDirection::DOWN => {
-                for x in 0..BOARD_SIZE {
-                    let new_pos = BoardCoordinates::new(x, self.cursor_position.y);
-                    if let Some(new_pos) = self.find_next_available_cell(&new_pos, &direction) {
-                        self.cursor_position = new_pos;
-                        return;
-                    }
-                }
-            }
-            MoveDirection::LEFT | MoveDirection::RIGHT => {
-                for y in 0..BOARD_SIZE {
-                    let new_pos = BoardCoordinates::new(self.cursor_position.x, y);
-                    if let Some(new_pos) = self.find_next_available_cell(&new_pos, &direction) {
-                        self.cursor_position = new_pos;
-                        return;
-                    }
-                }
+    fn index_lines(state: &BoardState, lines: &[Line]) -> Vec<Vec<usize>> {
+        let mut cell_lines = vec![vec![]; state.cells().count()];
+        for (index, line) in lines.iter().enumerate() {
+            for coords in line {
+                cell_lines[state.index(coords)].push(index);
             }
         }
+        cell_lines
     }
 
-    pub fn play(&mut self) {
-        if !self.is_game_over() {
-            self.state[self.cursor_position.x][self.cursor_position.y] = self.turn.clone();
-            self.turn = match self.turn {
-                Player::ONE => Player::TWO,
-                Player::TWO => Player::ONE,
-                _ => Player::ONE
-            };
-            if let Some(empty_cell) = self.find_empty_cell() {
-                self.cursor_position = empty_cell;
-            }
-            self.winner = self.compute_winner();
+    // A game already under way, `turn` being the player to move next
+    pub(crate) fn from_position(
+        rules: Rules,
+        state: BoardState,
+        line_length: usize,
+        lines: Arc<Vec<Line>>,
+        gravity: bool,
+        turn: Player,
+    ) -> Self {
+        let mut game = Self {
+            rules,
+            line_length,
+            turn,
+            mark: Player::ONE,
+            winner: Player::NONE,
+            cell_lines: Arc::new(TicTacToe::index_lines(&state, &lines)),
+            state,
+            gravity,
+            lines,
+            history: vec![],
+        };
+        if game.find_line(0..game.lines.len()) != Player::NONE {
+            game.winner = game.rules.winner_of_line(&game.turn.other());
         }
+        game
     }
 
-    fn find_empty_cell(&self) -> Option<BoardCoordinates> {
-        for (x, col) in self.state.iter().enumerate() {
-            for (y, cell) in col.iter().enumerate() {
-                if *cell == Player::NONE {
-                    return Some(BoardCoordinates { x, y });
-                }
-            }
-        }
-        None
+    pub fn rules(&self) -> Rules {
+        self.rules
     }
 
-    fn find_next_available_cell(&self, pos: &BoardCoordinates, direction: &MoveDirection) -> Option<BoardCoordinates> {
-        match direction {
-            MoveDirection::UP => {
-                let row = self.get_row(pos.x);
-                row.iter()
-                    .enumerate()
-                    .rev()
-                    .find(|(y, cell)| *y < pos.y && **cell == Player::NONE)
-                    .map(|(y, _)| BoardCoordinates::new(pos.x, y))
-            }
-            MoveDirection::DOWN => {
-                let row = self.get_row(pos.x);
-                row.iter()
-                    .enumerate()
-                    .find(|(y, cell)| *y > pos.y && **cell == Player::NONE)
-                    .map(|(x, _)| BoardCoordinates::new(pos.x, x))
-            }
-            MoveDirection::RIGHT => {
-                let col = self.get_col(pos.y);
-                col.iter()
-                    .enumerate()
-                    .find(|(x, cell )| *x > pos.x && **cell == Player::NONE)
-                    .map(|(x, _)| BoardCoordinates::new(x, pos.y))
+    pub fn turn(&self) -> &Player {
+        &self.turn
+    }
 
-            }
-            MoveDirection::LEFT => {
-                let col = self.get_col(pos.y);
-                col.iter()
-                    .enumerate()
-                    .rev()
-                    .find(|(x, cell )| *x < pos.x && **cell == Player::NONE)
-                    .map(|(x, _)| BoardCoordinates::new(x, pos.y))
+    pub fn winner(&self) -> &Player {
+        &self.winner
+    }
 
-            }
+    pub fn state(&self) -> &BoardState {
+        &self.state
+    }
+
+    pub fn gravity(&self) -> bool {
+        self.gravity
+    }
+
+    pub fn lines(&self) -> &[Line] {
+        &self.lines
+    }
+
+    // How many in a row win
+    pub fn line_length(&self) -> usize {
+        self.line_length
+    }
+
+    pub(crate) fn shared_lines(&self) -> Arc<Vec<Line>> {
+        self.lines.clone()
+    }
+
+    // Lowest empty cell of a column
+    pub fn drop_target(&self, x: usize, z: usize) -> Option<BoardCoordinates> {
+        (0..self.state.height()).rev()
+            .map(|y| BoardCoordinates::new(x, y).with_z(z))
+            .find(|coords| self.state.is_empty_at(coords))
+    }
+
+    pub fn toggle_mark(&mut self) {
+        if self.rules.can_choose_mark() {
+            self.mark = self.mark.other();
         }
     }
 
-    pub fn is_grid_filled(&self) -> bool {
-        self.state.iter().all(|row| row.iter().all(|cell| *cell != Player::NONE))
+    // The mark the current player would place
+    pub fn current_mark(&self) -> Player {
+        self.rules.mark(&self.turn, &self.mark)
     }
 
-    pub fn is_game_over(&self) -> bool {
-        self.winner != Player::NONE || self.is_grid_filled()
+    pub fn place(&mut self, coords: BoardCoordinates) -> Result<GameStatus, MoveError> {
+        self.place_mark(coords, self.current_mark())
     }
 
-    fn compute_winner(&self) -> Player {
-        for row in &self.state {
-            if row.iter().all(|cell| *cell == Player::ONE) {
-                return Player::ONE;
-            }
-            if row.iter().all(|cell| *cell == Player::TWO) {
-                return Player::TWO;
-            }
+    // Places the move's mark, which has to be one the current player may use
+    pub fn play_move(&mut self, mv: &Move) -> Result<GameStatus, MoveError> {
+        if self.rules.mark(&self.turn, &mv.mark) != mv.mark {
+            return Err(MoveError::WrongMark(mv.coords));
+        }
+        let status = self.place_mark(mv.coords, mv.mark.clone())?;
+        // The mark picked last is kept for the next turn
+        if self.rules.can_choose_mark() {
+            self.mark = mv.mark.clone();
         }
+        Ok(status)
+    }
 
-        // Check columns
-        for y in 0..BOARD_SIZE {
-            if self.state.iter().all(|row| row[y] == Player::ONE) {
-                return Player::ONE;
-            }
-            if self.state.iter().all(|row| row[y] == Player::TWO) {
-                return Player::TWO;
-            }
+    fn place_mark(&mut self, coords: BoardCoordinates, mark: Player) -> Result<GameStatus, MoveError> {
+        if self.is_game_over() {
+            return Err(MoveError::GameOver);
+        }
+        if !self.state.contains(&coords) {
+            return Err(MoveError::OutOfBounds(coords));
+        }
+        if !self.state.is_empty_at(&coords) {
+            return Err(MoveError::Occupied(coords));
+        }
+        if self.gravity && self.drop_target(coords.x, coords.z) != Some(coords) {
+            return Err(MoveError::Floating(coords));
         }
 
-        // Check main diagonal
-        if (0..BOARD_SIZE).all(|i| self.state[i][i] == Player::ONE) {
-            return Player::ONE;
+        let mover = self.turn.clone();
+        self.state.set(&coords, mark.clone());
+        self.history.push(Move { coords, mark });
+        self.turn = mover.other();
+        self.winner = self.compute_winner(&mover, &coords);
+
+        Ok(self.status())
+    }
+
+    // Every move the current player can make, both marks included when they get to choose
+    pub fn legal_moves(&self) -> Vec<Move> {
+        if self.is_game_over() {
+            return vec![];
         }
-        if (0..BOARD_SIZE).all(|i| self.state[i][i] == Player::TWO) {
-            return Player::TWO;
+
+        let cells: Vec<BoardCoordinates> = if self.gravity {
+            (0..self.state.depth())
+                .flat_map(|z| (0..self.state.width()).filter_map(move |x| self.drop_target(x, z)))
+                .collect()
+        } else {
+            self.state.cells().filter(|(_, cell)| **cell == Player::NONE).map(|(coords, _)| coords).collect()
+        };
+        let marks = if self.rules.can_choose_mark() {
+            vec![Player::ONE, Player::TWO]
+        } else {
+            vec![self.current_mark()]
+        };
+
+        cells.into_iter()
+            .flat_map(|coords| marks.iter().map(move |mark| Move { coords, mark: mark.clone() }))
+            .collect()
+    }
+
+    // Moves that would win on the spot for `player`, as if it were their turn
+    pub fn winning_moves(&self, player: &Player) -> Vec<Move> {
+        let mut game = self.clone();
+        game.turn = player.clone();
+        game.legal_moves().into_iter()
+            .filter(|mv| {
+                let mut next = game.clone();
+                next.play_move(mv) == Ok(GameStatus::Won(player.clone()))
+            })
+            .collect()
+    }
+
+    // Moves that would complete a line for `player`, as if it were their turn. The rules decide
+    // whether that wins or loses.
+    pub fn completing_moves(&self, player: &Player) -> Vec<Move> {
+        let mut game = self.clone();
+        game.turn = player.clone();
+        game.legal_moves().into_iter()
+            .filter(|mv| {
+                let mut next = game.clone();
+                matches!(next.play_move(mv), Ok(GameStatus::Won(_)))
+            })
+            .collect()
+    }
+
+    pub fn history(&self) -> &[Move] {
+        &self.history
+    }
+
+    // Takes back the last move, games that were decided are open again
+    pub fn undo(&mut self) -> Option<Move> {
+        let mv = self.history.pop()?;
+        self.state.set(&mv.coords, Player::NONE);
+        self.turn = self.turn.other();
+        self.winner = Player::NONE;
+        if self.rules.can_choose_mark() {
+            self.mark = mv.mark.clone();
         }
+        Some(mv)
+    }
+
+    // Plays for a given player whatever the turn, for games made of several boards
+    pub(crate) fn place_as(&mut self, player: Player, coords: BoardCoordinates) -> Result<GameStatus, MoveError> {
+        self.turn = player;
+        self.place(coords)
+    }
 
-        // Check anti-diagonal
-        if (0..BOARD_SIZE).all(|i| self.state[i][2 - i] == Player::ONE) {
-            return Player::ONE;
+    pub fn status(&self) -> GameStatus {
+        if self.winner != Player::NONE {
+            GameStatus::Won(self.winner.clone())
+        } else if self.is_grid_filled() {
+            GameStatus::Draw
+        } else {
+            GameStatus::InProgress
         }
-        if (0..BOARD_SIZE).all(|i| self.state[i][2 - i] == Player::TWO) {
-            return Player::TWO;
+    }
+
+    pub fn is_grid_filled(&self) -> bool {
+        self.state.is_filled()
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.winner != Player::NONE || self.is_grid_filled()
+    }
+
+    // Only lines through the cell just played can have been completed
+    fn compute_winner(&self, mover: &Player, played: &BoardCoordinates) -> Player {
+        let lines = self.cell_lines[self.state.index(played)].iter().copied();
+        match self.find_line(lines) {
+            Player::NONE => Player::NONE,
+            _ => self.rules.winner_of_line(mover),
+        }
+    }
+
+    // The mark that completed one of the lines, if any
+    fn find_line(&self, lines: impl Iterator<Item = usize>) -> Player {
+        for line in lines.map(|index| &self.lines[index]) {
+            let first = self.state.get(&line[0]);
+            if *first != Player::NONE && line.iter().all(|coords| self.state.get(coords) == first) {
+                return first.clone();
+            }
         }
 
         Player::NONE
     }
 
-    fn get_col(&self, y: usize) -> Vec<Player> {
-        self.state.iter().map(|row| row[y].clone()).collect()
+}
+
+// The game after playing `notations` like b2 from the empty board, for tests
+#[cfg(test)]
+pub(crate) fn played(rules: Rules, notations: &[&str]) -> TicTacToe {
+    played_on(TicTacToe::new(rules), notations)
+}
+
+#[cfg(test)]
+pub(crate) fn played_on(mut game: TicTacToe, notations: &[&str]) -> TicTacToe {
+    for notation in notations {
+        game.play_move(&Move::from_notation(notation, &game).unwrap()).unwrap();
     }
+    game
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    fn get_row(&self, x: usize) -> Vec<Player> {
-        self.state[x].clone()
+    fn cells(moves: Vec<Move>) -> Vec<String> {
+        moves.iter().map(|mv| mv.coords.to_notation()).collect()
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn completing_a_line_wins_or_loses_by_the_rules() {
+        let classic = played(Rules::Classic, &["a1", "c3", "a2", "c2"]);
+        assert_eq!(cells(classic.completing_moves(&Player::ONE)), ["a3"]);
+        assert_eq!(cells(classic.winning_moves(&Player::ONE)), ["a3"]);
+
+        let misere = played(Rules::Misere, &["a1", "c3", "a2", "c2"]);
+        assert_eq!(cells(misere.completing_moves(&Player::ONE)), ["a3"]);
+        assert!(misere.winning_moves(&Player::ONE).is_empty());
+    }
+
+    #[test]
+    fn rejected_moves_change_nothing() {
+        let mut game = played(Rules::Wild, &["b2 x"]);
+        let before = game.clone();
+        assert_eq!(game.play_move(&"b2 o".parse().unwrap()), Err(MoveError::Occupied(BoardCoordinates::new(1, 1))));
+        assert_eq!(game.current_mark(), before.current_mark());
+        assert_eq!(game.history(), before.history());
+
+        let mut classic = played(Rules::Classic, &["b2"]);
+        assert_eq!(classic.play_move(&"a1 x".parse().unwrap()), Err(MoveError::WrongMark(BoardCoordinates::new(0, 0))));
+        assert_eq!(classic.current_mark(), Player::TWO);
+    }
+
+    #[test]
+    fn undo_reopens_a_decided_game() {
+        let mut game = played(Rules::Classic, &["a1", "b1", "a2", "b2", "a3"]);
+        assert_eq!(game.status(), GameStatus::Won(Player::ONE));
+        assert_eq!(game.undo().map(|mv| mv.coords.to_notation()), Some("a3".to_string()));
+        assert_eq!(game.status(), GameStatus::InProgress);
+        assert_eq!(*game.turn(), Player::ONE);
+    }
+
+    #[test]
+    fn lines_longer_than_the_board_are_rejected() {
+        let long = BoardSize { width: Some(5), height: Some(3), line_length: Some(5) };
+        assert!(long.check(false).is_ok());
+        assert_eq!(long.new_game(Rules::Classic, false).line_length(), 5);
+        assert!(BoardSize { line_length: Some(4), ..BoardSize::default() }.check(false).is_err());
+        assert!(BoardSize { height: Some(3), line_length: Some(8), ..BoardSize::default() }.check(true).is_err());
+    }
+
+    #[test]
+    fn cubes_have_every_winning_line() {
+        assert_eq!(TicTacToe::cube(Rules::Classic, 3).lines().len(), 49);
+        assert_eq!(TicTacToe::cube(Rules::Classic, 4).lines().len(), 76);
+    }
+
+    #[test]
+    #[should_panic]
+    fn lines_of_no_cells_are_rejected() {
+        TicTacToe::sized(Rules::Classic, 3, 3, 0);
+    }
+}
@@ -1,5 +1,14 @@
+pub mod bitboard;
+pub mod board;
+pub mod cursor;
 pub mod game;
 pub mod movement;
+pub mod moves;
+pub mod notakto;
 pub mod player;
+pub mod rules;
+pub mod solver;
+pub mod symmetry;
+pub mod ultimate;
 
-pub mod coordinates;
\ No newline at end of file
+pub mod coordinates;
@@ -1,3 +1,6 @@
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum MoveDirection {
-    UP, DOWN, LEFT, RIGHT
+    UP, DOWN, LEFT, RIGHT,
+    // Between the layers of a 3D board
+    ABOVE, BELOW
 }
\ No newline at end of file
@@ -0,0 +1,88 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error};
+
+use crate::tictactoe::coordinates::BoardCoordinates;
+use crate::tictactoe::game::TicTacToe;
+use crate::tictactoe::player::Player;
+
+// A cell and the mark put on it
+#[derive(Clone, PartialEq, Debug)]
+pub struct Move {
+    pub coords: BoardCoordinates,
+    pub mark: Player,
+}
+
+impl Move {
+    // The cell then the mark, e.g. "b2 x"
+    pub fn to_notation(&self) -> String {
+        match self.mark {
+            Player::ONE => format!("{} x", self.coords.to_notation()),
+            Player::TWO => format!("{} o", self.coords.to_notation()),
+            Player::NONE => self.coords.to_notation(),
+        }
+    }
+
+    // The mark can be left out, it is then the one the player to move would place
+    pub fn from_notation(notation: &str, game: &TicTacToe) -> Option<Self> {
+        match notation.split_whitespace().count() {
+            1 => Some(Move { coords: BoardCoordinates::from_notation(notation)?, mark: game.current_mark() }),
+            _ => notation.parse().ok(),
+        }
+    }
+}
+
+// A cell and a mark that must both be given, e.g. "b2 x"
+impl FromStr for Move {
+    type Err = Error;
+
+    fn from_str(notation: &str) -> Result<Self, Self::Err> {
+        let invalid = || anyhow!("'{}' is not a move like b2 x.", notation);
+        let [cell, mark] = notation.split_whitespace().collect::<Vec<_>>()[..] else {
+            return Err(invalid());
+        };
+        let coords = BoardCoordinates::from_notation(cell).ok_or_else(invalid)?;
+        let mark = match mark.to_ascii_lowercase().as_str() {
+            "x" => Player::ONE,
+            "o" => Player::TWO,
+            _ => return Err(invalid()),
+        };
+        Ok(Move { coords, mark })
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum GameStatus {
+    InProgress,
+    Won(Player),
+    Draw,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MoveError {
+    GameOver,
+    OutOfBounds(BoardCoordinates),
+    Occupied(BoardCoordinates),
+    // With gravity only the lowest empty cell of a column can be played
+    Floating(BoardCoordinates),
+    // The current player may not place that mark there
+    WrongMark(BoardCoordinates),
+    // Ultimate tic-tac-toe sends the player to a given board
+    WrongBoard(BoardCoordinates),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveError::GameOver => write!(f, "the game is over"),
+            MoveError::OutOfBounds(coords) => write!(f, "{} is not on the board", coords.to_notation()),
+            MoveError::Occupied(coords) => write!(f, "{} is already taken", coords.to_notation()),
+            MoveError::Floating(coords) => write!(f, "{} is not the lowest empty cell of its column", coords.to_notation()),
+            MoveError::WrongMark(coords) => write!(f, "that mark cannot be placed on {}", coords.to_notation()),
+            MoveError::WrongBoard(coords) => write!(f, "board {} cannot be played", coords.to_notation()),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
@@ -0,0 +1,118 @@
+use crate::tictactoe::coordinates::BoardCoordinates;
+use crate::tictactoe::cursor::Cursor;
+use crate::tictactoe::game::TicTacToe;
+use crate::tictactoe::movement::MoveDirection;
+use crate::tictactoe::moves::{GameStatus, MoveError};
+use crate::tictactoe::player::Player;
+use crate::tictactoe::rules::Rules;
+
+// Notakto played over several boards. A board is dead once it holds a line,
+// and whoever kills the last board loses.
+pub struct Notakto {
+    boards: Vec<TicTacToe>,
+    // One per board, so each board remembers where the cursor was left
+    cursors: Vec<Cursor>,
+    turn: Player,
+    winner: Player,
+    active_board: usize,
+}
+
+impl Notakto {
+    pub fn new(board_count: usize) -> Self {
+        let board_count = board_count.max(1);
+        Self {
+            boards: (0..board_count).map(|_| TicTacToe::new(Rules::Notakto)).collect(),
+            cursors: vec![Cursor::default(); board_count],
+            turn: Player::ONE,
+            winner: Player::NONE,
+            active_board: 0,
+        }
+    }
+
+    pub fn boards(&self) -> &[TicTacToe] {
+        &self.boards
+    }
+
+    pub fn turn(&self) -> &Player {
+        &self.turn
+    }
+
+    pub fn winner(&self) -> &Player {
+        &self.winner
+    }
+
+    pub fn active_board(&self) -> usize {
+        self.active_board
+    }
+
+    // Someone always kills the last board, so there are no draws
+    pub fn status(&self) -> GameStatus {
+        match self.winner {
+            Player::NONE => GameStatus::InProgress,
+            _ => GameStatus::Won(self.winner.clone()),
+        }
+    }
+
+    pub fn is_board_dead(&self, board: usize) -> bool {
+        self.boards[board].is_game_over()
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.winner != Player::NONE
+    }
+
+    pub fn cursor_position(&self) -> BoardCoordinates {
+        self.cursors[self.active_board].position()
+    }
+
+    // Moving off the side of a board continues on the next live board
+    pub fn move_cursor(&mut self, direction: MoveDirection) {
+        let cursor = &mut self.cursors[self.active_board];
+        let previous = cursor.position();
+        cursor.move_towards(&self.boards[self.active_board], direction);
+
+        if cursor.position() == previous {
+            match direction {
+                MoveDirection::RIGHT => self.switch_board(1),
+                MoveDirection::LEFT => self.switch_board(self.boards.len() - 1),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn select_cell(&mut self, coords: BoardCoordinates) -> bool {
+        self.cursors[self.active_board].select(&self.boards[self.active_board], coords)
+    }
+
+    pub fn play(&mut self) -> Result<GameStatus, MoveError> {
+        if self.is_game_over() {
+            return Err(MoveError::GameOver);
+        }
+
+        let mover = self.turn.clone();
+        let board = &mut self.boards[self.active_board];
+        let cursor = &mut self.cursors[self.active_board];
+        board.place_as(mover.clone(), cursor.target(board).unwrap_or(cursor.position()))?;
+        cursor.follow(board);
+
+        self.turn = mover.other();
+        if self.boards.iter().all(|board| board.is_game_over()) {
+            self.winner = Rules::Notakto.winner_of_line(&mover);
+        } else if self.is_board_dead(self.active_board) {
+            self.switch_board(1);
+        }
+
+        Ok(self.status())
+    }
+
+    fn switch_board(&mut self, step: usize) {
+        let count = self.boards.len();
+        for offset in 1..=count {
+            let board = (self.active_board + step * offset) % count;
+            if !self.is_board_dead(board) {
+                self.active_board = board;
+                return;
+            }
+        }
+    }
+}
@@ -1,5 +1,15 @@
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub enum Player {
     ONE, TWO,
     NONE
-}
\ No newline at end of file
+}
+
+impl Player {
+    pub fn other(&self) -> Player {
+        match self {
+            Player::ONE => Player::TWO,
+            Player::TWO => Player::ONE,
+            _ => Player::ONE
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error};
+
+use crate::tictactoe::player::Player;
+
+// Cells hold marks rather than players: ONE is an X and TWO an O.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum Rules {
+    #[default]
+    Classic,
+    // Completing a line loses
+    Misere,
+    // Each turn the player picks whether to place an X or an O, any line wins
+    Wild,
+    // Both players place X, completing a line loses
+    Notakto,
+}
+
+impl Rules {
+    // The mark `player` puts down, `chosen` only matters when players pick their mark
+    pub fn mark(&self, player: &Player, chosen: &Player) -> Player {
+        match self {
+            Rules::Classic | Rules::Misere => player.clone(),
+            Rules::Wild => chosen.clone(),
+            Rules::Notakto => Player::ONE,
+        }
+    }
+
+    pub fn can_choose_mark(&self) -> bool {
+        *self == Rules::Wild
+    }
+
+    // Who wins once `mover` completes a line
+    pub fn winner_of_line(&self, mover: &Player) -> Player {
+        match self {
+            Rules::Classic | Rules::Wild => mover.clone(),
+            Rules::Misere | Rules::Notakto => mover.other(),
+        }
+    }
+}
+
+impl FromStr for Rules {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_lowercase().as_str() {
+            "classic" => Ok(Rules::Classic),
+            "misere" | "misère" => Ok(Rules::Misere),
+            "wild" => Ok(Rules::Wild),
+            "notakto" => Ok(Rules::Notakto),
+            _ => Err(anyhow!("Unknown rules '{}', expected classic, misere, wild or notakto.", name)),
+        }
+    }
+}
+
+impl fmt::Display for Rules {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rules::Classic => write!(f, "classic"),
+            Rules::Misere => write!(f, "misere"),
+            Rules::Wild => write!(f, "wild"),
+            Rules::Notakto => write!(f, "notakto"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_round_trip() {
+        for rules in [Rules::Classic, Rules::Misere, Rules::Wild, Rules::Notakto] {
+            assert_eq!(rules.to_string().parse::<Rules>().unwrap(), rules);
+        }
+        assert_eq!("Misère".parse::<Rules>().unwrap(), Rules::Misere);
+        assert!("gomoku".parse::<Rules>().is_err());
+    }
+
+    #[test]
+    fn marks_depend_on_the_rules() {
+        assert_eq!(Rules::Classic.mark(&Player::TWO, &Player::ONE), Player::TWO);
+        assert_eq!(Rules::Wild.mark(&Player::TWO, &Player::ONE), Player::ONE);
+        assert_eq!(Rules::Notakto.mark(&Player::TWO, &Player::TWO), Player::ONE);
+    }
+
+    #[test]
+    fn completing_a_line_loses_under_misere_and_notakto() {
+        assert_eq!(Rules::Classic.winner_of_line(&Player::ONE), Player::ONE);
+        assert_eq!(Rules::Wild.winner_of_line(&Player::TWO), Player::TWO);
+        assert_eq!(Rules::Misere.winner_of_line(&Player::ONE), Player::TWO);
+        assert_eq!(Rules::Notakto.winner_of_line(&Player::TWO), Player::ONE);
+    }
+}
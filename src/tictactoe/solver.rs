@@ -0,0 +1,247 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::tictactoe::bitboard::{bits, Bitboard, BitboardLayout};
+use crate::tictactoe::game::TicTacToe;
+use crate::tictactoe::moves::Move;
+use crate::tictactoe::player::Player;
+
+// Result of perfect play for the player to move
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Outcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Evaluation {
+    pub outcome: Outcome,
+    // Moves left until the game ends with both sides playing perfectly
+    pub distance: usize,
+}
+
+impl Evaluation {
+    // Higher is better: win as fast as possible, lose as late as possible
+    pub fn score(&self) -> i32 {
+        match self.outcome {
+            Outcome::Win => 1000 - self.distance as i32,
+            Outcome::Draw => 0,
+            Outcome::Loss => self.distance as i32 - 1000,
+        }
+    }
+
+    // The same position seen by the other player, one move earlier
+    fn previous_move(&self) -> Evaluation {
+        let outcome = match self.outcome {
+            Outcome::Win => Outcome::Loss,
+            Outcome::Draw => Outcome::Draw,
+            Outcome::Loss => Outcome::Win,
+        };
+        Evaluation { outcome, distance: self.distance + 1 }
+    }
+}
+
+// Plays every game out from a position. Positions are stored once per symmetry class,
+// so mirrored and rotated boards share their entry.
+pub struct Solver {
+    layout: BitboardLayout,
+    // Cell permutations of the board's symmetries
+    symmetries: Vec<Vec<usize>>,
+    table: HashMap<Bitboard, Evaluation>,
+}
+
+impl Solver {
+    // None when the board is too big for a bitboard
+    pub fn new(game: &TicTacToe) -> Option<Self> {
+        let layout = BitboardLayout::new(game)?;
+        Some(Self {
+            symmetries: symmetries(&layout),
+            layout,
+            table: HashMap::new(),
+        })
+    }
+
+    pub fn layout(&self) -> &BitboardLayout {
+        &self.layout
+    }
+
+    // Positions solved so far, one per symmetry class
+    pub fn table_size(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn evaluate(&mut self, game: &TicTacToe) -> Evaluation {
+        let bitboard = self.layout.encode(game);
+        self.solve(&bitboard)
+    }
+
+    pub fn solve(&mut self, bitboard: &Bitboard) -> Evaluation {
+        self.solve_until(bitboard, &|| false).expect("never stopped")
+    }
+
+    // None once `should_stop` returns true. Positions solved before that stay in the table,
+    // so asking again carries on where this left off.
+    pub fn solve_until(&mut self, bitboard: &Bitboard, should_stop: &dyn Fn() -> bool) -> Option<Evaluation> {
+        let key = self.canonical(bitboard);
+        if let Some(evaluation) = self.table.get(&key) {
+            return Some(*evaluation);
+        }
+        if should_stop() {
+            return None;
+        }
+
+        let evaluation = match children(&self.layout, bitboard) {
+            children if children.is_empty() => self.terminal(bitboard),
+            children => children.iter()
+                .map(|(_, child)| self.solve_until(child, should_stop).map(|evaluation| evaluation.previous_move()))
+                .collect::<Option<Vec<Evaluation>>>()?
+                .into_iter()
+                .max_by_key(Evaluation::score)
+                .expect("at least one move"),
+        };
+
+        self.table.insert(key, evaluation);
+        Some(evaluation)
+    }
+
+    // Every legal move with how it turns out for the player making it
+    pub fn evaluate_moves(&mut self, game: &TicTacToe) -> Vec<(Move, Evaluation)> {
+        self.evaluate_moves_until(game, &|| false).expect("never stopped")
+    }
+
+    pub fn evaluate_moves_until(&mut self, game: &TicTacToe, should_stop: &dyn Fn() -> bool) -> Option<Vec<(Move, Evaluation)>> {
+        let bitboard = self.layout.encode(game);
+        children(&self.layout, &bitboard).into_iter()
+            .map(|(mv, child)| Some((mv, self.solve_until(&child, should_stop)?.previous_move())))
+            .collect()
+    }
+
+    // All the moves that do as well as possible
+    pub fn best_moves(&mut self, game: &TicTacToe) -> Vec<Move> {
+        self.best_moves_until(game, &|| false).expect("never stopped")
+    }
+
+    pub fn best_moves_until(&mut self, game: &TicTacToe, should_stop: &dyn Fn() -> bool) -> Option<Vec<Move>> {
+        let moves = self.evaluate_moves_until(game, should_stop)?;
+        let best = moves.iter().map(|(_, evaluation)| evaluation.score()).max();
+        Some(moves.into_iter()
+            .filter(|(_, evaluation)| Some(evaluation.score()) == best)
+            .map(|(mv, _)| mv)
+            .collect())
+    }
+
+    fn terminal(&self, bitboard: &Bitboard) -> Evaluation {
+        let winner = self.layout.winner(bitboard);
+        let outcome = if winner == Player::NONE {
+            Outcome::Draw
+        } else if winner == *bitboard.turn() {
+            Outcome::Win
+        } else {
+            Outcome::Loss
+        };
+        Evaluation { outcome, distance: 0 }
+    }
+
+    // The smallest of the symmetric versions of a position
+    fn canonical(&self, bitboard: &Bitboard) -> Bitboard {
+        self.symmetries.iter()
+            .map(|permutation| {
+                let (x, o) = (bitboard.marks(&Player::ONE), bitboard.marks(&Player::TWO));
+                (permute(x, permutation), permute(o, permutation))
+            })
+            .min()
+            .map(|(x, o)| Bitboard::from_marks(x, o, bitboard.turn().clone()))
+            .expect("the identity is always a symmetry")
+    }
+}
+
+// Number of different positions that can come up in a game started from `game`, itself included
+pub fn reachable_positions(game: &TicTacToe) -> Option<usize> {
+    let layout = BitboardLayout::new(game)?;
+    let mut seen = HashSet::new();
+    let mut pending = vec![layout.encode(game)];
+    while let Some(bitboard) = pending.pop() {
+        if seen.insert(bitboard.clone()) {
+            pending.extend(children(&layout, &bitboard).into_iter().map(|(_, child)| child));
+        }
+    }
+    Some(seen.len())
+}
+
+fn children(layout: &BitboardLayout, bitboard: &Bitboard) -> Vec<(Move, Bitboard)> {
+    let rules = layout.rules();
+    let marks = if rules.can_choose_mark() { vec![Player::ONE, Player::TWO] } else { vec![Player::ONE] };
+
+    bits(layout.moves(bitboard))
+        .flat_map(|index| marks.iter().map(move |chosen| (index, chosen)))
+        .map(|(index, chosen)| {
+            let mv = Move {
+                coords: layout.coordinates(index),
+                mark: rules.mark(bitboard.turn(), chosen),
+            };
+            (mv, layout.play(bitboard, index, chosen))
+        })
+        .collect()
+}
+
+fn permute(mask: u64, permutation: &[usize]) -> u64 {
+    bits(mask).fold(0, |permuted, index| permuted | 1 << permutation[index])
+}
+
+// Rotations and reflections of every layer, as cell permutations. With gravity the bottom
+// has to stay the bottom, which leaves the mirror image.
+fn symmetries(layout: &BitboardLayout) -> Vec<Vec<usize>> {
+    let board = layout.board();
+    board.symmetries().into_iter()
+        .filter(|symmetry| !layout.gravity() || symmetry.keeps_rows())
+        .map(|symmetry| {
+            let mut permutation = vec![0; layout.cell_count()];
+            for (coords, _) in board.cells() {
+                let transformed = coords.transform(symmetry, board.width(), board.height());
+                permutation[board.index(&coords)] = board.index(&transformed);
+            }
+            permutation
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tictactoe::game::played;
+    use crate::tictactoe::rules::Rules;
+
+    #[test]
+    fn classic_board_has_5478_reachable_positions() {
+        let game = TicTacToe::new(Rules::Classic);
+        assert_eq!(reachable_positions(&game), Some(5478));
+    }
+
+    #[test]
+    fn classic_board_is_a_draw_over_765_canonical_positions() {
+        let game = TicTacToe::new(Rules::Classic);
+        let mut solver = Solver::new(&game).unwrap();
+        assert_eq!(solver.evaluate(&game).outcome, Outcome::Draw);
+        assert_eq!(solver.table_size(), 765);
+    }
+
+    #[test]
+    fn misere_is_a_draw_and_wild_a_first_player_win() {
+        for (rules, outcome) in [(Rules::Misere, Outcome::Draw), (Rules::Wild, Outcome::Win)] {
+            let game = TicTacToe::new(rules);
+            assert_eq!(Solver::new(&game).unwrap().evaluate(&game).outcome, outcome);
+        }
+    }
+
+    #[test]
+    fn best_moves_take_a_win() {
+        let game = played(Rules::Classic, &["a1", "b1", "a2", "b2"]);
+        let best = Solver::new(&game).unwrap().best_moves(&game);
+        assert_eq!(best, vec![Move::from_notation("a3", &game).unwrap()]);
+    }
+
+    #[test]
+    fn boards_over_64_cells_are_not_solved() {
+        assert!(Solver::new(&TicTacToe::sized(Rules::Classic, 9, 9, 5)).is_none());
+    }
+}
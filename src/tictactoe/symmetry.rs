@@ -0,0 +1,90 @@
+// The rotations and reflections of a board. Rotations turn clockwise, and only
+// Identity, Rotate180 and the horizontal and vertical flips fit a board that isn't square.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    // Mirrors left and right
+    FlipHorizontal,
+    // Mirrors top and bottom
+    FlipVertical,
+    // Swaps rows and columns, keeping the top left corner in place
+    FlipDiagonal,
+    // Swaps rows and columns, keeping the top right corner in place
+    FlipAntiDiagonal,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::FlipHorizontal,
+        Symmetry::FlipVertical,
+        Symmetry::FlipDiagonal,
+        Symmetry::FlipAntiDiagonal,
+    ];
+
+    // The symmetry that undoes this one
+    pub fn inverse(&self) -> Symmetry {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            other => *other,
+        }
+    }
+
+    // Whether a width x height board keeps its shape
+    pub fn fits(&self, width: usize, height: usize) -> bool {
+        width == height || matches!(self, Symmetry::Identity | Symmetry::Rotate180 | Symmetry::FlipHorizontal | Symmetry::FlipVertical)
+    }
+
+    // Whether every cell stays on its row, so gravity still pulls the same way
+    pub fn keeps_rows(&self) -> bool {
+        matches!(self, Symmetry::Identity | Symmetry::FlipHorizontal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tictactoe::coordinates::BoardCoordinates;
+    use crate::tictactoe::game::played;
+    use crate::tictactoe::rules::Rules;
+
+    #[test]
+    fn inverses_undo_every_symmetry() {
+        for symmetry in Symmetry::ALL {
+            for (x, y) in [(0, 0), (2, 0), (1, 2), (3, 1)] {
+                let coords = BoardCoordinates::new(x, y);
+                let back = coords.transform(symmetry, 4, 4).transform(symmetry.inverse(), 4, 4);
+                assert_eq!(back, coords, "{:?}", symmetry);
+            }
+        }
+    }
+
+    #[test]
+    fn rotations_turn_clockwise() {
+        let corner = BoardCoordinates::new(0, 0);
+        assert_eq!(corner.transform(Symmetry::Rotate90, 3, 3), BoardCoordinates::new(2, 0));
+        assert_eq!(corner.transform(Symmetry::Rotate180, 3, 3), BoardCoordinates::new(2, 2));
+        assert_eq!(corner.transform(Symmetry::Rotate270, 3, 3), BoardCoordinates::new(0, 2));
+    }
+
+    #[test]
+    fn only_four_symmetries_fit_a_rectangle() {
+        assert_eq!(Symmetry::ALL.iter().filter(|symmetry| symmetry.fits(7, 6)).count(), 4);
+        assert!(Symmetry::ALL.iter().all(|symmetry| symmetry.fits(3, 3)));
+    }
+
+    #[test]
+    fn corners_share_a_canonical_position() {
+        let positions: Vec<_> = ["a1", "c1", "a3", "c3"].iter()
+            .map(|notation| played(Rules::Classic, &[notation]).state().canonical().0)
+            .collect();
+        assert!(positions.iter().all(|position| *position == positions[0]));
+    }
+}
@@ -0,0 +1,168 @@
+use crate::tictactoe::coordinates::BoardCoordinates;
+use crate::tictactoe::cursor::Cursor;
+use crate::tictactoe::game::TicTacToe;
+use crate::tictactoe::movement::MoveDirection;
+use crate::tictactoe::moves::{GameStatus, MoveError};
+use crate::tictactoe::player::Player;
+
+const BOARD_SIZE: usize = 3;
+
+// A 3x3 grid of tic-tac-toe boards. The cell you play in picks the board your opponent plays
+// in next, and winning three boards in a row wins the game.
+pub struct UltimateTicTacToe {
+    boards: Vec<Vec<TicTacToe>>,
+    // Holds the winner of every sub-board that has been won
+    meta: TicTacToe,
+    turn: Player,
+    winner: Player,
+    // None when the next player may pick any open board
+    active_board: Option<BoardCoordinates>,
+    cursor_board: BoardCoordinates,
+    cursor_cell: BoardCoordinates,
+}
+
+impl Default for UltimateTicTacToe {
+    fn default() -> Self {
+        let boards = (0..BOARD_SIZE)
+            .map(|_| (0..BOARD_SIZE).map(|_| TicTacToe::default()).collect())
+            .collect();
+
+        Self {
+            boards,
+            meta: TicTacToe::default(),
+            turn: Player::ONE,
+            winner: Player::NONE,
+            active_board: None,
+            cursor_board: BoardCoordinates::new(1, 1),
+            cursor_cell: BoardCoordinates::new(1, 1),
+        }
+    }
+}
+
+impl UltimateTicTacToe {
+    pub fn board(&self, coords: BoardCoordinates) -> &TicTacToe {
+        &self.boards[coords.x][coords.y]
+    }
+
+    pub fn turn(&self) -> &Player {
+        &self.turn
+    }
+
+    pub fn winner(&self) -> &Player {
+        &self.winner
+    }
+
+    pub fn active_board(&self) -> Option<BoardCoordinates> {
+        self.active_board
+    }
+
+    pub fn cursor_board(&self) -> BoardCoordinates {
+        self.cursor_board
+    }
+
+    pub fn cursor_cell(&self) -> BoardCoordinates {
+        self.cursor_cell
+    }
+
+    pub fn status(&self) -> GameStatus {
+        if self.winner != Player::NONE {
+            GameStatus::Won(self.winner.clone())
+        } else if self.is_game_over() {
+            GameStatus::Draw
+        } else {
+            GameStatus::InProgress
+        }
+    }
+
+    pub fn is_board_open(&self, coords: BoardCoordinates) -> bool {
+        !self.board(coords).is_game_over()
+    }
+
+    pub fn is_board_playable(&self, coords: BoardCoordinates) -> bool {
+        self.is_board_open(coords) && self.active_board.is_none_or(|active| active == coords)
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.winner != Player::NONE || self.all_boards().all(|coords| !self.is_board_open(coords))
+    }
+
+    // The cursor moves cell by cell over the 9x9 grid, held inside the active board if there is one
+    pub fn move_cursor(&mut self, direction: MoveDirection) {
+        let size = BOARD_SIZE * BOARD_SIZE;
+        let (x, y) = (
+            self.cursor_board.x * BOARD_SIZE + self.cursor_cell.x,
+            self.cursor_board.y * BOARD_SIZE + self.cursor_cell.y,
+        );
+        let (x, y) = match direction {
+            MoveDirection::LEFT => (x.saturating_sub(1), y),
+            MoveDirection::RIGHT => ((x + 1).min(size - 1), y),
+            MoveDirection::UP => (x, y.saturating_sub(1)),
+            MoveDirection::DOWN => (x, (y + 1).min(size - 1)),
+            MoveDirection::ABOVE | MoveDirection::BELOW => (x, y),
+        };
+
+        let board = BoardCoordinates::new(x / BOARD_SIZE, y / BOARD_SIZE);
+        if self.active_board.is_none_or(|active| active == board) {
+            self.cursor_board = board;
+            self.cursor_cell = BoardCoordinates::new(x % BOARD_SIZE, y % BOARD_SIZE);
+        }
+    }
+
+    // Picks a cell of the board under the cursor
+    pub fn select_cell(&mut self, coords: BoardCoordinates) -> bool {
+        if coords.x >= BOARD_SIZE || coords.y >= BOARD_SIZE {
+            return false;
+        }
+        self.cursor_cell = coords;
+        true
+    }
+
+    pub fn play(&mut self) -> Result<GameStatus, MoveError> {
+        let board_coords = self.cursor_board;
+        let cell = self.cursor_cell;
+        if self.is_game_over() {
+            return Err(MoveError::GameOver);
+        }
+        if !self.is_board_playable(board_coords) {
+            return Err(MoveError::WrongBoard(board_coords));
+        }
+
+        let mover = self.turn.clone();
+        let board = &mut self.boards[board_coords.x][board_coords.y];
+        board.place_as(mover.clone(), cell)?;
+
+        if let GameStatus::Won(board_winner) = board.status() {
+            self.meta.place_as(board_winner, board_coords)?;
+            self.winner = self.meta.winner().clone();
+        }
+
+        self.turn = mover.other();
+        self.active_board = if self.is_board_open(cell) { Some(cell) } else { None };
+        self.move_cursor_to_open_board();
+
+        Ok(self.status())
+    }
+
+    fn move_cursor_to_open_board(&mut self) {
+        let board = match self.active_board {
+            Some(board) => Some(board),
+            None if self.is_board_open(self.cursor_board) => None,
+            None => self.all_boards().find(|coords| self.is_board_open(*coords)),
+        };
+
+        if let Some(board) = board {
+            self.cursor_board = board;
+            let mut cursor = Cursor::default();
+            let sub_board = self.board(board);
+            if cursor.select(sub_board, self.cursor_cell) {
+                return;
+            }
+            cursor.follow(sub_board);
+            self.cursor_cell = cursor.position();
+        }
+    }
+
+    fn all_boards(&self) -> impl Iterator<Item = BoardCoordinates> {
+        (0..BOARD_SIZE).flat_map(|x| (0..BOARD_SIZE).map(move |y| BoardCoordinates::new(x, y)))
+    }
+}